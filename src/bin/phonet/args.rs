@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[clap(author, version)]
@@ -6,27 +6,47 @@ use clap::Parser;
 ///
 /// More information: https://github.com/dxrcy/phonet
 pub struct Args {
+    /// Command to run instead of tests (optional)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Custom tests (optional)
     ///
     /// This overrides all tests in the file
+    ///
+    /// To test a word which is the name of a command, write it after `--`
+    ///
+    /// Eg. `phonet -- explain` or `phonet -f myfile. -- fmt kato`
     pub tests: Vec<String>,
 
     /// Name and path of file to run and test
     ///
     /// If name ends with a period, the 'phonet' extension is implied
-    /// 
+    ///
     /// Eg. `phonet -f myfile.phonet` or `phonet -f myfile.` (same result)
-    /// 
-    /// If name ends with a slash, the '/phonet' file name is implied 
+    ///
+    /// If name ends with a slash, the '/phonet' file name is implied
     ///
     /// Eg. `phonet -f folder/phonet` or `phonet -f folder/` (same result)
     #[arg(short, long, default_value_t = String::from("phonet"))]
     pub file: String,
 
+    /// Select tag for conditional statements (optional)
+    ///
+    /// Statements with a condition, such as `[strict] + ^<C>+$`, are only included if the condition is met
+    ///
+    /// Eg. `phonet -t strict -t formal`
+    #[arg(short, long = "tag")]
+    pub tags: Vec<String>,
+
     /// Don't display passes and notes, only fails
     #[arg(short, long)]
     pub quiet: bool,
 
+    /// Display every rule which a failing test is invalid against, not just the first
+    #[arg(short, long)]
+    pub all_reasons: bool,
+
     /// Minify file and save
     #[arg(short, long)]
     pub minify: bool,
@@ -55,9 +75,141 @@ pub struct Args {
     #[arg(long = "gmax", default_value_t = 20)]
     pub generate_max_len: usize,
 
+    /// Format of test results
+    ///
+    /// Use `json`, `tap`, or `junit` for machine-readable output, such as for CI
+    ///
+    /// The program exits with a non-zero code if any test fails, for every format
+    #[arg(long, value_enum, default_value_t = Format::Text, conflicts_with = "generate")]
+    pub format: Format,
+
     /// Display output in default color
     ///
-    /// Use for piping standard output to a file
+    /// Color is also not used if standard output is not a terminal, such as when piping to a file
     #[arg(short, long)]
     pub no_color: bool,
 }
+
+/// Format of test results
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Human-readable text, with color
+    Text,
+    /// JSON object, with tests grouped by notes
+    Json,
+    /// Test Anything Protocol (version 13)
+    Tap,
+    /// JUnit XML
+    Junit,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Explain why words are valid or invalid, step by step
+    ///
+    /// Every rule is evaluated in order, showing its line, pattern (with classes substituted), whether it matched, and whether it is satisfied
+    ///
+    /// Eg. `phonet explain taso` or `phonet -f myfile. explain taso kili`
+    Explain {
+        /// Words to explain
+        #[arg(required = true)]
+        words: Vec<String>,
+    },
+
+    /// Evolve words with sound changes, and check them against the rules of the file
+    ///
+    /// Sound changes are the `@change` directives of another file, applied in order. Each evolved word is validated against the rules of the file given with `-f`
+    ///
+    /// The program exits with a non-zero code if any evolved word is invalid
+    ///
+    /// Eg. `phonet -f daughter.phonet evolve -c proto.phonet kato pita` or `phonet -f daughter. evolve -c proto. -l lexicon.txt --history`
+    Evolve {
+        /// Name and path of file with sound changes
+        ///
+        /// Shorthand is expanded the same as `--file`
+        #[arg(short, long)]
+        changes: String,
+
+        /// Words to evolve (optional)
+        words: Vec<String>,
+
+        /// File of words to evolve, separated by spaces or lines (optional)
+        ///
+        /// Text after `#` on a line is ignored
+        #[arg(short, long)]
+        lexicon: Option<String>,
+
+        /// Display every sound change which altered each word
+        #[arg(long)]
+        history: bool,
+    },
+
+    /// Expand file into readable source, such as a minified file
+    ///
+    /// Classes, notes, rules and tests are written on separate lines, and formatted. Comments are not kept
+    ///
+    /// Eg. `phonet -f myfile.min.phonet expand` or `phonet -f myfile.min.phonet expand -o myfile.phonet`
+    Expand {
+        /// File to save expanded source to (optional)
+        ///
+        /// Prints to standard output if not given
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Format file in canonical style, and save
+    ///
+    /// Comments are kept, rules are indented under notes, tests are indented under rules, and classes and comments are aligned
+    ///
+    /// Eg. `phonet fmt` or `phonet -f myfile. fmt --check`
+    Fmt {
+        /// Don't save file, only check if it is formatted
+        ///
+        /// The program exits with a non-zero code if the file is not formatted, such as for CI
+        #[arg(long)]
+        check: bool,
+
+        /// Style of class name brackets in patterns
+        ///
+        /// Defaults to the style which is used most in the file
+        #[arg(long, value_enum)]
+        brackets: Option<Brackets>,
+
+        /// Maximum width of lines of tests, before words are wrapped onto another line
+        #[arg(long, default_value_t = 80)]
+        width: usize,
+    },
+}
+
+/// Style of class name brackets in patterns
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Brackets {
+    /// `<` and `>`
+    Ascii,
+    /// `⟨` and `⟩`
+    Unicode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_names_can_be_tested() {
+        let tests = |args: &[&str]| {
+            let args = Args::try_parse_from(args).unwrap();
+            assert!(args.command.is_none());
+            args.tests
+        };
+
+        assert_eq!(tests(&["phonet", "--", "explain"]), ["explain"]);
+        assert_eq!(
+            tests(&["phonet", "-f", "a.", "--", "fmt", "kato"]),
+            ["fmt", "kato"]
+        );
+
+        let args = Args::try_parse_from(["phonet", "-f", "a.", "explain", "kato"]).unwrap();
+        assert_eq!(args.file, "a.");
+        assert!(matches!(args.command, Some(Command::Explain { words }) if words == ["kato"]));
+    }
+}
@@ -2,22 +2,31 @@ mod args;
 #[macro_use]
 mod utils;
 
-use std::{fs, path::Path};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::Path,
+    process,
+};
 
 use clap::Parser;
 use stilo::println_styles;
 
 use phonet::{
     draft::{Message::Test, TestDraft},
-    get_min_filename, DisplayLevel, Draft,
+    get_min_filename,
+    outcome::EvalMode,
+    syntax::{self, FormatOptions, SyntaxTree},
+    DisplayLevel, Draft,
 };
 
-use crate::args::Args;
-use crate::utils::format_filename;
+use crate::args::{Args, Brackets, Command, Format};
+use crate::utils::{display_errors, format_filename};
 
 fn main() -> Result<(), String> {
     let args = Args::parse();
-    let do_color = !args.no_color;
+    // Spans of failing tests are underlined with carets, without color
+    let do_color = !args.no_color && io::stdout().is_terminal();
 
     // Format filename (expand shorthand)
     let filename = format_filename(args.file);
@@ -32,26 +41,131 @@ fn main() -> Result<(), String> {
         throw!("Filename is a directory '{}'. Tip: End filename with '/' to use 'phonet' file in that directory", filename);
     }
 
-    // Read file
-    let file = match fs::read_to_string(&filename) {
-        Ok(x) => x,
-        Err(err) => throw!("Failed to read file: `{:?}`", err),
+    // Format file instead of running tests
+    // File does not need to be valid
+    if let Some(Command::Fmt {
+        check,
+        brackets,
+        width,
+    }) = &args.command
+    {
+        let file = try_or_throw!(fs::read_to_string(path));
+
+        let formatted = SyntaxTree::from(&file).format(&FormatOptions {
+            brackets: brackets.map(|brackets| match brackets {
+                Brackets::Ascii => syntax::Brackets::Ascii,
+                Brackets::Unicode => syntax::Brackets::Unicode,
+            }),
+            width: *width,
+        });
+
+        // Already formatted
+        if formatted == file {
+            println_styles!("File '{}' is formatted": Green if do_color, filename);
+            return Ok(());
+        }
+
+        // Check only
+        if *check {
+            println_styles!("File '{}' is not formatted": Red + bold if do_color, filename);
+            process::exit(1);
+        }
+
+        if let Err(err) = fs::write(path, formatted) {
+            throw!("Failed to write formatted file: `{:?}`", err);
+        }
+        println_styles!("Formatted file '{}'": Green if do_color, filename);
+        return Ok(());
+    }
+
+    // Read and parse file, with any included files, and selected tags
+    let tags: Vec<_> = args.tags.iter().map(String::as_str).collect();
+    let mut draft = match Draft::from_path_with_tags(path, &tags) {
+        Ok(draft) => draft,
+
+        // Display every error in file
+        Err(err) => {
+            display_errors(&err, do_color);
+            throw!("Failed to parse file '{}'", filename);
+        }
     };
 
-    // Parse file
-    let mut draft = try_or_throw!(Draft::from(&file));
+    // Explain words instead of running tests
+    if let Some(Command::Explain { words }) = &args.command {
+        for word in words {
+            draft.explain(word).display(do_color);
+        }
+        return Ok(());
+    }
+
+    // Evolve words with sound changes instead of running tests
+    if let Some(Command::Evolve {
+        changes,
+        words,
+        lexicon,
+        history,
+    }) = &args.command
+    {
+        // Words given as arguments, then words of lexicon file
+        let mut words = words.clone();
+        if let Some(lexicon) = lexicon {
+            let file = try_or_throw!(fs::read_to_string(lexicon));
+            for line in file.lines() {
+                let line = line.split('#').next().unwrap_or_default();
+                words.extend(line.split_whitespace().map(String::from));
+            }
+        }
+        if words.is_empty() {
+            throw!("No words to evolve. Tip: Give words as arguments, or use `--lexicon`");
+        }
+
+        // Read and parse file of sound changes, with the same tags
+        let changes_filename = format_filename(changes.clone());
+        let changes_path = Path::new(&changes_filename);
+        if !changes_path.is_file() {
+            throw!("File not found '{}'", changes_filename);
+        }
+        let changes = match Draft::from_path_with_tags(changes_path, &tags) {
+            Ok(changes) => changes,
+            Err(err) => {
+                display_errors(&err, do_color);
+                throw!("Failed to parse file '{}'", changes_filename);
+            }
+        };
+
+        let words: Vec<_> = words.iter().map(String::as_str).collect();
+        let mut evolution = try_or_throw!(changes.evolve(&words, *history));
+        try_or_throw!(evolution.validate(&draft));
+        evolution.display(do_color);
+
+        // Exit with error code if any evolved word is invalid
+        if evolution.invalid_count() > 0 {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Expand file to readable source instead of running tests
+    if let Some(Command::Expand { output }) = &args.command {
+        let source = draft.to_source();
+
+        match output {
+            Some(output) => {
+                if let Err(err) = fs::write(output, source) {
+                    throw!("Failed to write expanded file: `{:?}`", err);
+                }
+            }
+            None => print!("{}", source),
+        }
+        return Ok(());
+    }
 
     // Use custom CLI tests if given
     if !args.tests.is_empty() {
         draft.messages = args
             .tests
             .iter()
-            .map(|x| {
-                Test(TestDraft {
-                    intent: true,
-                    word: x.to_string(),
-                })
-            })
+            .map(|x| Test(TestDraft::new(true, x)))
             .collect();
     }
 
@@ -72,13 +186,35 @@ fn main() -> Result<(), String> {
         DisplayLevel::ShowAll
     };
 
-    // Print name before outcome, if given
-    if let Some(name) = &draft.name {
-        println_styles!("{}": Cyan + italic if do_color, name);
-    }
+    // Report every violated rule, or only the first
+    let eval_mode = if args.all_reasons {
+        EvalMode::AllViolations
+    } else {
+        EvalMode::FirstViolation
+    };
+
+    // Run tests
+    let outcome = draft.run_with(eval_mode);
+
+    // Display outcome in format
+    match args.format {
+        Format::Text => {
+            // Print name before outcome, if given
+            if let Some(name) = &draft.name {
+                println_styles!("{}": Cyan + italic if do_color, name);
+            }
+
+            if args.all_reasons {
+                outcome.display_all_reasons(display_level, do_color);
+            } else {
+                outcome.display(display_level, do_color);
+            }
+        }
 
-    // Run tests and display
-    draft.run().display(display_level, do_color);
+        Format::Json => println!("{}", outcome.to_json()),
+        Format::Tap => println!("{}", outcome.to_tap()),
+        Format::Junit => println!("{}", outcome.to_junit()),
+    }
 
     // Generate and display words
     if let Some(count) = args.generate {
@@ -109,5 +245,10 @@ fn main() -> Result<(), String> {
         }
     }
 
+    // Exit with error code if any test failed
+    if outcome.fail_count > 0 {
+        process::exit(1);
+    }
+
     Ok(())
 }
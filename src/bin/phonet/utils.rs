@@ -1,3 +1,8 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use phonet::error::{Error, Location};
+use stilo::stylize;
+
 /// Unwrap the `Ok` value of a `Result`, or exit with a stringified `Error`
 macro_rules! try_or_throw {
     ( $result: expr ) => {{
@@ -24,6 +29,33 @@ macro_rules! throw {
     };
 }
 
+/// Display every error to standard error, with a snippet of the file where the error occurred
+pub fn display_errors(err: &Error, do_color: bool) {
+    // Contents of files which errors occurred in
+    let mut sources: HashMap<&Path, Option<String>> = HashMap::new();
+
+    for err in err.list() {
+        // Get source of file, if error has a location
+        let source = match err {
+            Error::Parse(
+                _,
+                Location {
+                    file: Some(file), ..
+                },
+            ) => sources
+                .entry(file)
+                .or_insert_with(|| fs::read_to_string(file).ok())
+                .as_deref(),
+            _ => None,
+        };
+
+        match source {
+            Some(source) => eprintln!("{}\n", err.display_snippet(source, do_color)),
+            None => eprintln!("{}\n", stylize!("{}": Red if do_color, err)),
+        }
+    }
+}
+
 /// Format filename when used with shorthand
 pub fn format_filename(name: String) -> String {
     // Add 'phonet' file extension if file argument ends with a period
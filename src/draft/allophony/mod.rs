@@ -0,0 +1,279 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+
+use fancy_regex::{Regex, RegexBuilder};
+
+use super::{macros::Macros, replace::replace_classes, statements::span_of, Classes, Draft};
+use crate::error::{Error, Location, ParseError};
+
+/// Allophonic rewrite rule, such as `t > tʰ / #_`, with a single `@allophone` directive
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct RawAllophone {
+    /// Rule as written in file, without `@allophone`
+    pub rule: String,
+    /// Location of rule
+    pub location: Location,
+}
+
+/// Allophonic rewrite rule, with patterns compiled
+#[derive(Debug, Clone)]
+pub(crate) struct Allophone {
+    /// Matches end of text before target, if the rule has an environment before the target
+    before: Option<Regex>,
+    /// Matches target at start of text, followed by the environment after the target, if any
+    target: Regex,
+    /// Text which replaces each match of target
+    replacement: String,
+    /// Location of rule
+    location: Location,
+}
+
+/// Parts of an allophone rule, as slices of the rule
+#[derive(Debug, PartialEq)]
+struct Parts<'a> {
+    /// Pattern which is replaced
+    target: &'a str,
+    /// Text which replaces target, which is empty for `∅`
+    replacement: &'a str,
+    /// Pattern before target, which may begin with `#` for the start of the word
+    before: &'a str,
+    /// Pattern after target, which may end with `#` for the end of the word
+    after: &'a str,
+}
+
+impl fmt::Display for RawAllophone {
+    /// Convert rule to `@allophone` directive
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@allophone {}", self.rule)
+    }
+}
+
+// Patterns and replacement are compared, as regex cannot be compared
+impl PartialEq for Allophone {
+    fn eq(&self, other: &Self) -> bool {
+        self.before.as_ref().map(Regex::as_str) == other.before.as_ref().map(Regex::as_str)
+            && self.target.as_str() == other.target.as_str()
+            && self.replacement == other.replacement
+    }
+}
+
+impl Draft {
+    /// Derive narrow transcription of a word in broad transcription, by applying every allophone rule in order
+    ///
+    /// Each rule replaces every match of its target in the word, in the environment of the word before the rule was applied.
+    /// Returns an error if a pattern fails to run on the word (such as exceeding the backtrack limit)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::Draft;
+    /// let draft = Draft::from("$V = [aeiou]; @allophone t > tʰ / #_; @allophone t > ɾ / <V>_<V>").unwrap();
+    ///
+    /// assert_eq!(draft.derive("tata").unwrap(), "tʰaɾa");
+    /// ```
+    pub fn derive(&self, word: &str) -> Result<String, Error> {
+        apply_allophones(word, &self.allophones)
+    }
+}
+
+/// Apply every allophone rule to word, in order
+pub(super) fn apply_allophones(word: &str, allophones: &[Allophone]) -> Result<String, Error> {
+    let mut word = word.to_string();
+    for allophone in allophones {
+        word = allophone.apply(&word)?;
+    }
+    Ok(word)
+}
+
+impl Allophone {
+    /// Replace every match of target in word, from left to right
+    ///
+    /// Environment before each match is checked in the original word, so every match is replaced at once
+    pub(super) fn apply(&self, word: &str) -> Result<String, Error> {
+        let fail = |err| Error::RuleFail(word.to_string(), self.location.clone(), Box::new(err));
+
+        let mut output = String::new();
+        let mut index = 0;
+
+        while let Some(ch) = word[index..].chars().next() {
+            let is_after = match &self.before {
+                Some(before) => before.is_match(&word[..index]).map_err(fail)?,
+                None => true,
+            };
+
+            // Empty matches are skipped, so every character is only replaced once
+            let found = if is_after {
+                self.target.find(&word[index..]).map_err(fail)?
+            } else {
+                None
+            };
+
+            match found {
+                Some(found) if !found.as_str().is_empty() => {
+                    output.push_str(&self.replacement);
+                    index += found.end();
+                }
+                _ => {
+                    output.push(ch);
+                    index += ch.len_utf8();
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Check syntax of allophone rule, such as `t > tʰ / #_`
+pub(super) fn check_allophone(rule: &str) -> Result<(), ParseError> {
+    parse_allophone(rule).map(|_| ())
+}
+
+/// Split allophone rule into target, replacement, and environment (optional)
+fn parse_allophone(rule: &str) -> Result<Parts<'_>, ParseError> {
+    let invalid = || ParseError::InvalidAllophone(rule.trim().to_string());
+
+    let (change, environment) = match split_outside_classes(rule, '/') {
+        Some((change, environment)) => (change, Some(environment)),
+        None => (rule, None),
+    };
+
+    let (target, replacement) = split_outside_classes(change, '>').ok_or_else(invalid)?;
+    let (target, replacement) = (target.trim(), replacement.trim());
+    if target.is_empty() || replacement.contains(char::is_whitespace) {
+        return Err(invalid());
+    }
+    let replacement = if replacement == "∅" {
+        ""
+    } else {
+        replacement
+    };
+
+    let (before, after) = match environment {
+        Some(environment) => {
+            let (before, after) = split_outside_classes(environment, '_').ok_or_else(invalid)?;
+            if split_outside_classes(after, '_').is_some() {
+                return Err(invalid());
+            }
+            (before.trim(), after.trim())
+        }
+        None => ("", ""),
+    };
+
+    Ok(Parts {
+        target,
+        replacement,
+        before,
+        after,
+    })
+}
+
+/// Split text at the first separator which is not inside a class reference, such as `<C>`
+///
+/// Angle brackets of look-behinds, named groups, and atomic groups are not class references or separators,
+/// the same as with `replace_angle_brackets`
+fn split_outside_classes(text: &str, separator: char) -> Option<(&str, &str)> {
+    let mut depth = 0;
+
+    for (index, ch) in text.char_indices() {
+        // Such as `(?<=`, `(?P<name>`, `\k<name>`, or `(?>`
+        let before = &text[..index];
+        let is_group = before.ends_with("(?") || before.ends_with("(?P") || before.ends_with("\\k");
+
+        match ch {
+            _ if is_group => (),
+            _ if ch == separator && depth == 0 => {
+                return Some((&text[..index], &text[index + ch.len_utf8()..]));
+            }
+            '<' | '⟨' => depth += 1,
+            '>' | '⟩' if depth > 0 => depth -= 1,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Compile each allophone rule, with classes substituted
+///
+/// Rules which fail to compile are skipped, and the error is added to `errors`
+pub(super) fn compile_allophones(
+    allophones: &[RawAllophone],
+    classes: &Classes,
+    macros: &Macros,
+    backtrack_limit: Option<usize>,
+    errors: &mut Vec<Error>,
+) -> Vec<Allophone> {
+    allophones
+        .iter()
+        .filter_map(|allophone| {
+            match compile_allophone(
+                &allophone.rule,
+                &allophone.location,
+                classes,
+                macros,
+                backtrack_limit,
+            ) {
+                Ok(allophone) => Some(allophone),
+                Err(err) => {
+                    errors.push(err);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Compile a single rewrite rule, such as an allophone or a sound change
+pub(super) fn compile_allophone(
+    rule: &str,
+    location: &Location,
+    classes: &Classes,
+    macros: &Macros,
+    backtrack_limit: Option<usize>,
+) -> Result<Allophone, Error> {
+    let parts = parse_allophone(rule).map_err(|kind| Error::Parse(kind, location.clone()))?;
+
+    // Substitute classes, with errors relative to the part of the rule
+    let expand = |part: &str| {
+        replace_classes(
+            part,
+            classes,
+            macros,
+            &location.sub_span(span_of(rule, part)),
+        )
+    };
+    let compile = |pattern: String| {
+        let mut builder = RegexBuilder::new(&pattern);
+        if let Some(limit) = backtrack_limit {
+            builder.backtrack_limit(limit);
+        }
+        builder
+            .build()
+            .map_err(|err| Error::Parse(ParseError::RegexParseFail(err), location.clone()))
+    };
+
+    let target = expand(parts.target)?;
+    // `#` is the start or end of the word
+    let before = match parts.before.strip_prefix('#') {
+        Some(before) => Some(format!("^(?:{})$", expand(before)?)),
+        None if parts.before.is_empty() => None,
+        None => Some(format!("(?:{})$", expand(parts.before)?)),
+    };
+    let after = match parts.after.strip_suffix('#') {
+        Some(after) => format!("(?={}$)", expand(after)?),
+        None if parts.after.is_empty() => String::new(),
+        None => format!("(?={})", expand(parts.after)?),
+    };
+    let target = format!("^(?:{}){}", target, after);
+
+    Ok(Allophone {
+        before: before.map(compile).transpose()?,
+        target: compile(target)?,
+        replacement: parts.replacement.to_string(),
+        location: location.clone(),
+    })
+}
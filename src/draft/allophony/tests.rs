@@ -0,0 +1,215 @@
+use super::*;
+use crate::{
+    draft::Message,
+    outcome::{DisplayLevel, PassStatus},
+    DraftBuilder,
+};
+
+#[test]
+fn parse_allophone_works() {
+    assert_eq!(
+        parse_allophone("t > tʰ / #_").unwrap(),
+        Parts {
+            target: "t",
+            replacement: "tʰ",
+            before: "#",
+            after: "",
+        }
+    );
+    assert_eq!(
+        parse_allophone("<C> > ∅ / <V>_#").unwrap(),
+        Parts {
+            target: "<C>",
+            replacement: "",
+            before: "<V>",
+            after: "#",
+        }
+    );
+    assert_eq!(
+        parse_allophone("a>b").unwrap(),
+        Parts {
+            target: "a",
+            replacement: "b",
+            before: "",
+            after: "",
+        }
+    );
+    // Underscores and angle brackets of classes are not separators
+    assert_eq!(
+        parse_allophone("<Geminate_C> > x / <_> _").unwrap(),
+        Parts {
+            target: "<Geminate_C>",
+            replacement: "x",
+            before: "<_>",
+            after: "",
+        }
+    );
+
+    // Look-behinds and atomic groups are not class references or separators
+    assert_eq!(
+        parse_allophone("(?>t) > d / (?<=a)_(?<!b)").unwrap(),
+        Parts {
+            target: "(?>t)",
+            replacement: "d",
+            before: "(?<=a)",
+            after: "(?<!b)",
+        }
+    );
+    let draft = Draft::from("@allophone t > d / (?<![st])<V>_<V>; $V = [aeiou]").unwrap();
+    assert_eq!(draft.derive("atata").unwrap(), "adata");
+    assert_eq!(draft.derive("asata").unwrap(), "asata");
+
+    for rule in ["t / _", "> a", "t > tʰ / #", "t > tʰ / _a_", "t > a b"] {
+        assert!(
+            matches!(parse_allophone(rule), Err(ParseError::InvalidAllophone(_))),
+            "'{}' should be invalid",
+            rule
+        );
+    }
+}
+
+#[test]
+fn derive_works() {
+    let draft = Draft::from(
+        "
+        $V = [aeiou]
+        @allophone t > tʰ / #_
+        @allophone t > ɾ / <V>_<V>
+        @allophone n > ŋ / _k
+        @allophone ə > ∅ / _#
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(draft.derive("tata").unwrap(), "tʰaɾa");
+    assert_eq!(draft.derive("atat").unwrap(), "aɾat");
+    assert_eq!(draft.derive("tanka").unwrap(), "tʰaŋka");
+    assert_eq!(draft.derive("kanə").unwrap(), "kan");
+    assert_eq!(draft.derive("").unwrap(), "");
+
+    // Rules are applied in order
+    let draft = Draft::from("@allophone a > b; @allophone b > c").unwrap();
+    assert_eq!(draft.derive("ab").unwrap(), "cc");
+
+    // Every match is replaced at once, in the environment of the original word
+    let draft = Draft::from("@allophone a > b / a_").unwrap();
+    assert_eq!(draft.derive("aaa").unwrap(), "abb");
+
+    // Feature bundles can be used in rules
+    let draft = Draft::from(
+        "@features p t -voice; @features b d +voice; @allophone <[+voice]> > <[-voice]>ʰ / _#",
+    );
+    assert!(draft.is_ok());
+    let draft = Draft::from(
+        "@features p t -voice; @features b d +voice; @allophone <[-voice]> > x / _<[+voice]>",
+    )
+    .unwrap();
+    assert_eq!(draft.derive("ptdp").unwrap(), "pxdp");
+}
+
+#[test]
+fn phonemic_tests_are_derived() {
+    let draft = Draft::from(
+        "
+        ~[]
+        $V = [aeiou]
+        @allophone t > tʰ / #_
+        @allophone t > ɾ / <V>_<V>
+        + ^ (tʰ | [kaeiou] | ɾ)+ $
+        ?+ /tata/ [kʰa]
+        ?! /kta/ [tata]
+        ",
+    )
+    .unwrap();
+
+    let tests: Vec<_> = draft
+        .messages
+        .iter()
+        .filter_map(|msg| match msg {
+            Message::Test(test) => Some((test.word.as_str(), test.underlying.as_deref())),
+            Message::Info(_) => None,
+        })
+        .collect();
+    assert_eq!(
+        tests,
+        [
+            ("tʰaɾa", Some("tata")),
+            ("kʰa", None),
+            ("kta", Some("kta")),
+            ("tata", None),
+        ]
+    );
+
+    let outcome = draft.run();
+    assert_eq!(outcome.fail_count, 1);
+    assert!(matches!(
+        &outcome.messages[1],
+        Message::Test(test) if test.status.is_fail() && test.underlying.is_none()
+    ));
+
+    // Both forms are displayed
+    let mut output = Vec::new();
+    outcome
+        .display_with(&mut output, DisplayLevel::ShowAll, false)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("✔ /tata/ → tʰaɾa  pass"));
+    assert!(output.contains("✘ /kta/ → kta     pass"));
+
+    let draft = DraftBuilder::new()
+        .mode(crate::draft::Mode::Narrow)
+        .allophone("k > kʰ")
+        .rule(false, "k(?!ʰ)")
+        .test(true, "/kat/")
+        .build()
+        .unwrap();
+    assert!(matches!(
+        &draft.run().messages[0],
+        Message::Test(test) if test.word == "kʰat" && test.status == PassStatus::Pass
+    ));
+}
+
+#[test]
+fn allophones_return_error() {
+    let Err(Error::Multiple(errors)) =
+        Draft::from("@allophone t > tʰ / #\n@allophone <C> > x / a_\n~[]\n?+ /Ta/")
+    else {
+        panic!("Should return multiple errors");
+    };
+
+    assert!(matches!(
+        &errors[0],
+        Error::Parse(ParseError::InvalidAllophone(rule), Location { line: 1, .. }) if rule == "t > tʰ / #"
+    ));
+    assert!(matches!(
+        &errors[1],
+        Error::Parse(ParseError::ClassNotFound(name, _), Location { line: 2, span: Some(span), .. })
+            if name == "C" && *span == (11..14)
+    ));
+    assert!(matches!(
+        &errors[2],
+        Error::Parse(
+            ParseError::NonIpaCharacter(_, 'T'),
+            Location { line: 4, .. }
+        )
+    ));
+    assert_eq!(errors.len(), 3);
+
+    // Broad tests are not accepted without allophones
+    assert!(matches!(
+        Draft::from("~[]\n?+ [kato]\n?+ /kato/"),
+        Err(Error::Parse(ParseError::NoAllophones(word), Location { line: 3, .. })) if word == "/kato/"
+    ));
+}
+
+#[test]
+fn allophones_are_converted_to_source() {
+    let draft = Draft::from("~[]; @allophone t>tʰ / #_; ?+ /ta/ [tʰa]").unwrap();
+
+    let source = draft.to_source();
+    assert_eq!(source, "~[]\n\n@allophone t>tʰ / #_\n\n?+ /ta/ tʰa\n");
+
+    let parsed = Draft::from(&source).unwrap();
+    assert_eq!(parsed.messages, draft.messages);
+    assert_eq!(parsed.allophones, draft.allophones);
+}
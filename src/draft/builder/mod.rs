@@ -0,0 +1,509 @@
+#[cfg(test)]
+mod tests;
+
+use super::{
+    allophony::{apply_allophones, check_allophone, compile_allophones, RawAllophone},
+    changes::{check_change, compile_changes, RawChange},
+    features::{parse_features, resolve_bundles, FeatureRow},
+    macros::{check_macros, split_parameters, ClassMacro, Macros},
+    orthography::{parse_mapping, strip_brackets, Mapping},
+    parse::{check_class_name, parse_rules},
+    prelude::{prelude, prelude_classes},
+    replace::resolve_classes,
+    transcription::strip_delimiters,
+    Classes, Draft,
+    Message::{self, *},
+    Mode, Note, RawRule, TestDraft,
+};
+use crate::error::{
+    Error, Location,
+    ParseError::{
+        EmptyNote, EmptyTestReason, InvalidBacktrackLimit, InvalidFeature, InvalidTestWord,
+        NoAllophones, NoFeatureSegments, NoFeatures, TranscriptionNotRomanized, UnknownPrelude,
+    },
+};
+
+/// Build a `Draft` with methods, instead of parsing a file
+///
+/// Each item is validated and compiled the same as the equivalent statement in a file.
+/// Errors are collected, and returned by `DraftBuilder::build`
+///
+/// Items (classes, rules, notes, tests, and backtrack limit) have no file location.
+/// Instead, the line number of each item is its position in the builder, starting at 1
+///
+/// # Examples
+///
+/// ```
+/// # use phonet::DraftBuilder;
+/// let draft = DraftBuilder::new()
+///     .class("C", "[ptk]")
+///     .class("V", "[aeiou]")
+///     .class("_", "<C> | <V>")
+///     .note("Invalid letters")
+///     .rule(true, "^ <_>+ $")
+///     .note("Vowel clusters")
+///     .rule(false, "<V>{2}")
+///     .test(true, "kato")
+///     .test_reason("taeko", "Vowel clusters")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(draft.rules.len(), 2);
+/// assert_eq!(draft.test_count, 2);
+/// assert_eq!(draft.run().fail_count, 0);
+/// ```
+///
+/// # Errors
+///
+/// If any item is invalid, such as a rule which uses a class which does not exist.
+///
+/// ```
+/// # use phonet::DraftBuilder;
+/// use phonet::error::{Error, ParseError::*};
+///
+/// let draft = DraftBuilder::new().rule(true, "<C>").build();
+///
+/// assert!(matches!(draft, Err(
+///     Error::Parse(ClassNotFound(name, None), _)
+/// ) if name == "C"));
+/// ```
+#[derive(Debug, Default)]
+pub struct DraftBuilder {
+    pub(super) messages: Vec<Message<TestDraft>>,
+    pub(super) name: Option<String>,
+    pub(super) mode: Mode,
+    pub(super) backtrack_limit: Option<usize>,
+
+    // Classes and rules, without regex parsed
+    pub(super) raw_rules: Vec<RawRule>,
+    pub(super) raw_classes: Classes,
+    pub(super) macros: Macros,
+    pub(super) preludes: Vec<String>,
+    pub(super) features: Vec<FeatureRow>,
+    pub(super) orthography: Vec<Mapping>,
+    pub(super) raw_allophones: Vec<RawAllophone>,
+    pub(super) raw_changes: Vec<RawChange>,
+
+    /// Location of each test, in order, for errors of test words
+    pub(super) test_locations: Vec<Location>,
+    /// Most recent note, used as reason for following rules
+    pub(super) last_note: Option<Note>,
+    /// Errors of every invalid item
+    pub(super) errors: Vec<Error>,
+    /// Amount of items added, for line number of location
+    pub(super) item_count: usize,
+}
+
+impl DraftBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set name of language
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set transcription mode
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set maximum amount of backtracking for a rule to run on a single word
+    ///
+    /// Same as `@backtrack-limit`. Limit must be greater than 0
+    pub fn backtrack_limit(mut self, limit: usize) -> Self {
+        let location = self.next_location();
+
+        if limit == 0 {
+            self.errors.push(Error::Parse(
+                InvalidBacktrackLimit(limit.to_string()),
+                location,
+            ));
+        } else {
+            self.backtrack_limit = Some(limit);
+        }
+        self
+    }
+
+    /// Add a class, with a name and pattern
+    ///
+    /// Same as `$name = pattern`. Use a name with parameters for a class macro, such as `Geminate(x)`
+    pub fn class(mut self, name: &str, pattern: &str) -> Self {
+        let location = self.next_location();
+
+        let (name, parameters) = match split_parameters(name.trim()) {
+            Ok(split) => split,
+            Err(kind) => {
+                self.errors.push(Error::Parse(kind, location));
+                return self;
+            }
+        };
+
+        if let Err(err) = check_class_name(name, &self.raw_classes, &self.macros, &location) {
+            self.errors.push(err);
+            return self;
+        }
+
+        let pattern = pattern.trim().to_string();
+        match parameters {
+            Some(parameters) => {
+                self.macros.insert(
+                    name.to_string(),
+                    ClassMacro {
+                        parameters,
+                        pattern,
+                        location,
+                    },
+                );
+            }
+            None => {
+                self.raw_classes
+                    .insert(name.to_string(), (pattern, location));
+            }
+        }
+        self
+    }
+
+    /// Import classes of a built-in prelude, such as `ipa`
+    ///
+    /// Same as `@prelude name`. Classes with the same name as a prelude class shadow it
+    pub fn prelude(mut self, name: &str) -> Self {
+        let location = self.next_location();
+        let name = name.trim();
+
+        if prelude(name).is_none() {
+            self.errors
+                .push(Error::Parse(UnknownPrelude(name.to_string()), location));
+        } else if !self.preludes.iter().any(|other| other == name) {
+            self.preludes.push(name.to_string());
+        }
+        self
+    }
+
+    /// Give features to segments, such as `-voice` or `+nasal`
+    ///
+    /// Same as `@features` with segments, then features
+    pub fn features(mut self, segments: &[&str], features: &[&str]) -> Self {
+        let location = self.next_location();
+
+        if segments.is_empty() {
+            self.errors.push(Error::Parse(NoFeatureSegments, location));
+            return self;
+        }
+        if features.is_empty() {
+            self.errors.push(Error::Parse(NoFeatures, location));
+            return self;
+        }
+
+        match parse_features(features) {
+            Ok(features) => self.features.push(FeatureRow {
+                segments: segments
+                    .iter()
+                    .map(|segment| segment.trim().to_string())
+                    .collect(),
+                features,
+                location,
+            }),
+            Err(feature) => self
+                .errors
+                .push(Error::Parse(InvalidFeature(feature.to_string()), location)),
+        }
+        self
+    }
+
+    /// Map a grapheme of the orthography to a phoneme, for transcription of tests
+    ///
+    /// Same as `@map grapheme /phoneme/`
+    pub fn map(mut self, grapheme: &str, phoneme: &str) -> Self {
+        let location = self.next_location();
+
+        match parse_mapping(&format!("{} {}", grapheme, phoneme)) {
+            Ok((grapheme, phoneme)) => self.orthography.push(Mapping {
+                grapheme,
+                phoneme,
+                location,
+            }),
+            Err(kind) => self.errors.push(Error::Parse(kind, location)),
+        }
+        self
+    }
+
+    /// Add an allophonic rewrite rule, such as `t > tʰ / #_`, to derive narrow transcriptions of tests
+    ///
+    /// Same as `@allophone rule`
+    pub fn allophone(mut self, rule: &str) -> Self {
+        let location = self.next_location();
+
+        match check_allophone(rule) {
+            Ok(()) => self.raw_allophones.push(RawAllophone {
+                rule: rule.trim().to_string(),
+                location,
+            }),
+            Err(kind) => self.errors.push(Error::Parse(kind, location)),
+        }
+        self
+    }
+
+    /// Add a sound change, such as `a > e / _i`, to evolve words with `Draft::evolve`
+    ///
+    /// Same as `@change rule`
+    pub fn change(mut self, rule: &str) -> Self {
+        let location = self.next_location();
+
+        match check_change(rule) {
+            Ok(()) => self.raw_changes.push(RawChange {
+                rule: rule.trim().to_string(),
+                location,
+            }),
+            Err(kind) => self.errors.push(Error::Parse(kind, location)),
+        }
+        self
+    }
+
+    /// Add a rule, using the most recent note as its reason
+    ///
+    /// Same as `+ pattern` if `intent` is true, or `! pattern` if false
+    pub fn rule(mut self, intent: bool, pattern: &str) -> Self {
+        let location = self.next_location();
+
+        self.raw_rules.push(RawRule {
+            pattern: pattern.trim().to_string(),
+            intent,
+            note: self.last_note.clone(),
+            location,
+        });
+        self
+    }
+
+    /// Add a note, which is displayed, and used as the reason for following rules
+    ///
+    /// Same as `* note`
+    pub fn note(self, note: &str) -> Self {
+        self.add_note(note, true)
+    }
+
+    /// Set the reason for following rules, without displaying it
+    ///
+    /// Same as `*: note`
+    pub fn quiet_note(self, note: &str) -> Self {
+        self.add_note(note, false)
+    }
+
+    /// Add a test, which should be valid if `intent` is true, or invalid if false
+    ///
+    /// Same as `?+ word` if `intent` is true, or `?! word` if false.
+    /// The word may be written with the delimiters of the mode, such as `/kato/`
+    pub fn test(self, intent: bool, word: &str) -> Self {
+        self.add_test(intent, word, None, None)
+    }
+
+    /// Add a test with the expected broad transcription of the word
+    ///
+    /// Same as `?+ word /transcription/` if `intent` is true, or `?! word /transcription/` if false
+    pub fn test_transcription(self, intent: bool, word: &str, transcription: &str) -> Self {
+        let transcription = strip_brackets(transcription.trim(), '/', '/').to_string();
+        self.add_test(intent, word, None, Some(transcription))
+    }
+
+    /// Add a test which should be invalid, with the note of the first rule it should violate
+    ///
+    /// Same as `?!(reason) word`
+    pub fn test_reason(mut self, word: &str, reason: &str) -> Self {
+        let reason = reason.trim();
+
+        if reason.is_empty() {
+            let location = self.next_location();
+            self.errors.push(Error::Parse(EmptyTestReason, location));
+            return self;
+        }
+
+        self.add_test(false, word, Some(Note(reason.to_string())), None)
+    }
+
+    /// Resolve classes and compile rules, and create `Draft`
+    ///
+    /// Returns every error of invalid items, classes, and rules, as a single error or `Error::Multiple`
+    pub fn build(self) -> Result<Draft, Error> {
+        let Self {
+            mut messages,
+            name,
+            mode,
+            backtrack_limit,
+            raw_rules,
+            raw_classes,
+            macros,
+            preludes,
+            features,
+            orthography,
+            raw_allophones,
+            raw_changes,
+            test_locations,
+            mut errors,
+            ..
+        } = self;
+
+        // Classes of preludes, which are shadowed by classes with the same name
+        let mut all_classes = prelude_classes(&preludes);
+        all_classes.extend(raw_classes.clone());
+
+        // Resolve feature bundles, then substitute classes in classes, then rules, allophones, and sound changes
+        let bundles = resolve_bundles(
+            &features,
+            &all_classes,
+            &macros,
+            &raw_rules,
+            &raw_allophones,
+            &raw_changes,
+            &mut errors,
+        );
+        let classes = resolve_classes(&all_classes, &macros, bundles, &mut errors);
+        check_macros(&macros, &classes, &mut errors);
+        let rules = parse_rules(&raw_rules, &classes, &macros, backtrack_limit, &mut errors);
+        let allophones = compile_allophones(
+            &raw_allophones,
+            &classes,
+            &macros,
+            backtrack_limit,
+            &mut errors,
+        );
+        let changes = compile_changes(
+            &raw_changes,
+            &classes,
+            &macros,
+            backtrack_limit,
+            &mut errors,
+        );
+
+        // Remove delimiters of mode from test words, and check transcriptions
+        let tests = messages.iter_mut().filter_map(|msg| match msg {
+            Test(test) => Some(test),
+            Info(_) => None,
+        });
+        for (index, test) in tests.enumerate() {
+            let location = || test_locations.get(index).cloned().unwrap_or_default();
+
+            // Derive narrow transcription of broad transcription, such as `/kato/`
+            let is_slashed =
+                test.word.len() > 2 && test.word.starts_with('/') && test.word.ends_with('/');
+            if mode == Mode::Narrow && is_slashed {
+                // Word would not be derived
+                if raw_allophones.is_empty() {
+                    errors.push(Error::Parse(NoAllophones(test.word.clone()), location()));
+                    continue;
+                }
+
+                let underlying = &test.word[1..test.word.len() - 1];
+                match apply_allophones(underlying, &allophones) {
+                    Ok(word) => {
+                        test.underlying = Some(underlying.to_string());
+                        test.word = word;
+                    }
+                    Err(err) => {
+                        errors.push(err);
+                        continue;
+                    }
+                }
+            }
+
+            match strip_delimiters(&test.word, mode) {
+                Ok(word) => test.word = word,
+                Err(kind) => errors.push(Error::Parse(kind, location())),
+            }
+
+            // Transcriptions are only given to romanized words
+            if test.transcription.is_some() && mode != Mode::Romanized {
+                errors.push(Error::Parse(
+                    TranscriptionNotRomanized(test.word.clone()),
+                    location(),
+                ));
+            }
+        }
+
+        // Get amount of tests in messages
+        let test_count = messages.iter().filter(|msg| msg.is_test()).count();
+
+        // Return single error, or all errors
+        match errors.len() {
+            0 => (),
+            1 => return Err(errors.remove(0)),
+            _ => return Err(Error::Multiple(errors)),
+        }
+
+        Ok(Draft {
+            rules,
+            raw_rules,
+            messages,
+            mode,
+            name,
+            test_count,
+            backtrack_limit,
+            raw_classes,
+            macros,
+            preludes,
+            features,
+            orthography,
+            raw_allophones,
+            allophones,
+            raw_changes,
+            changes,
+            classes,
+        })
+    }
+
+    /// Get location of next item, with its position as line number
+    fn next_location(&mut self) -> Location {
+        self.item_count += 1;
+        Location {
+            line: self.item_count,
+            ..Default::default()
+        }
+    }
+
+    /// Add note, and display it if `is_shown` is true
+    fn add_note(mut self, note: &str, is_shown: bool) -> Self {
+        let location = self.next_location();
+        let note = note.trim();
+
+        if note.is_empty() {
+            self.errors.push(Error::Parse(EmptyNote, location));
+            return self;
+        }
+
+        if is_shown {
+            self.messages.push(Info(Note(note.to_string())));
+        }
+        self.last_note = Some(Note(note.to_string()));
+        self
+    }
+
+    /// Add test, if word is not empty and does not contain whitespace
+    fn add_test(
+        mut self,
+        intent: bool,
+        word: &str,
+        reason: Option<Note>,
+        transcription: Option<String>,
+    ) -> Self {
+        let location = self.next_location();
+
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            self.errors
+                .push(Error::Parse(InvalidTestWord(word.to_string()), location));
+            return self;
+        }
+
+        self.messages.push(Test(TestDraft {
+            word: word.to_string(),
+            intent,
+            reason,
+            transcription,
+            underlying: None,
+        }));
+        self.test_locations.push(location);
+        self
+    }
+}
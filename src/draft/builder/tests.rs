@@ -0,0 +1,137 @@
+use super::*;
+use crate::error::ParseError::*;
+
+/// Example file, equivalent to `example_builder`
+const EXAMPLE: &str = "
+~/Example/
+$C = [ptk]
+$V = [aeiou]
+$_ = <C> | <V>
+* Invalid letters
+  + ^ <_>+ $
+*: No vowel clusters
+  ! <V>{2}
+?+ kato ta
+?!(No vowel clusters) taeko
+";
+
+/// Builder, equivalent to `EXAMPLE`
+fn example_builder() -> DraftBuilder {
+    DraftBuilder::new()
+        .name("Example")
+        .mode(Mode::Broad)
+        .class("C", "[ptk]")
+        .class("V", "[aeiou]")
+        .class("_", "<C> | <V>")
+        .note("Invalid letters")
+        .rule(true, "^ <_>+ $")
+        .quiet_note("No vowel clusters")
+        .rule(false, "<V>{2}")
+        .test(true, "kato")
+        .test(true, "ta")
+        .test_reason("taeko", "No vowel clusters")
+}
+
+#[test]
+fn build_matches_parse() {
+    let built = example_builder().build().unwrap();
+    let parsed = Draft::from(EXAMPLE).unwrap();
+
+    assert_eq!(built.rules, parsed.rules);
+    assert_eq!(built.messages, parsed.messages);
+    assert_eq!(built.name, parsed.name);
+    assert_eq!(built.mode, parsed.mode);
+    assert_eq!(built.test_count, 3);
+    assert_eq!(built.classes, {
+        // Locations differ
+        let mut classes = parsed.classes.clone();
+        for (name, (_, location)) in classes.iter_mut() {
+            *location = built.classes[name].1.clone();
+        }
+        classes
+    });
+
+    assert_eq!(built.minify(true).unwrap(), parsed.minify(true).unwrap());
+    assert_eq!(built.run().fail_count, 0);
+    assert!(built.generator(3..=5).is_ok());
+}
+
+#[test]
+fn build_locations_are_positions() {
+    let draft = example_builder().build().unwrap();
+
+    // Name and mode are not items
+    assert_eq!(draft.raw_classes["C"].1.line, 1);
+    assert_eq!(draft.rules[0].location.line, 5);
+    assert_eq!(draft.rules[1].location.line, 7);
+}
+
+#[test]
+fn build_invalid_items_fails() {
+    let err = DraftBuilder::new()
+        .class("a b", "x")
+        .class("C", "[ptk]")
+        .class("C", "[ptk]")
+        .note("  ")
+        .test(true, "two words")
+        .test(true, "")
+        .test_reason("ka", "")
+        .backtrack_limit(0)
+        .build()
+        .unwrap_err();
+
+    let Error::Multiple(errors) = err else {
+        panic!("Should be multiple errors");
+    };
+    let errors: Vec<_> = errors
+        .into_iter()
+        .map(|err| match err {
+            Error::Parse(kind, location) => (kind.to_string(), location.line),
+            _ => panic!("Should be parse error"),
+        })
+        .collect();
+
+    assert_eq!(
+        errors,
+        vec![
+            (InvalidClassName("a b".to_string()).to_string(), 1),
+            (ClassAlreadyExists("C".to_string()).to_string(), 3),
+            (EmptyNote.to_string(), 4),
+            (InvalidTestWord("two words".to_string()).to_string(), 5),
+            (InvalidTestWord(String::new()).to_string(), 6),
+            (EmptyTestReason.to_string(), 7),
+            (InvalidBacktrackLimit("0".to_string()).to_string(), 8),
+        ]
+    );
+}
+
+#[test]
+fn build_invalid_rule_fails() {
+    let err = DraftBuilder::new()
+        .class("C", "[ptk]")
+        .rule(true, "<c>")
+        .build()
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Parse(ClassNotFound(name, Some(closest)), Location { line: 2, .. })
+            if name == "c" && closest == "C"
+    ));
+
+    let err = DraftBuilder::new().rule(true, "(").build().unwrap_err();
+    assert!(matches!(err, Error::Parse(RegexParseFail(_), _)));
+}
+
+#[test]
+fn build_backtrack_limit_works() {
+    let draft = DraftBuilder::new()
+        .backtrack_limit(100)
+        .rule(false, r"(a*)*\1b")
+        .test(true, "aaaaaaaaaaaaaaaaaaaaaaaa")
+        .build()
+        .unwrap();
+
+    assert_eq!(draft.backtrack_limit, Some(100));
+    assert_eq!(draft.run().fail_count, 1);
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+
+use super::{
+    allophony::{check_allophone, compile_allophone, Allophone},
+    macros::Macros,
+    Classes,
+};
+use crate::error::{Error, Location, ParseError};
+
+/// Sound change, such as `a > e / _i`, with a single `@change` directive
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct RawChange {
+    /// Rule as written in file, without `@change`
+    pub rule: String,
+    /// Location of rule
+    pub location: Location,
+}
+
+/// Sound change, with patterns compiled
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Change {
+    /// Rule as written in file, without `@change`
+    pub rule: String,
+    /// Location of rule
+    pub location: Location,
+    /// Compiled rule, which is applied the same way as an allophone
+    rewrite: Allophone,
+}
+
+impl fmt::Display for RawChange {
+    /// Convert rule to `@change` directive
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@change {}", self.rule)
+    }
+}
+
+impl Change {
+    /// Replace every match of target in word, in the environment of the word before the change
+    pub(crate) fn apply(&self, word: &str) -> Result<String, Error> {
+        self.rewrite.apply(word)
+    }
+}
+
+/// Check syntax of sound change, such as `a > e / _i`
+///
+/// Sound changes have the same syntax as allophones
+pub(super) fn check_change(rule: &str) -> Result<(), ParseError> {
+    check_allophone(rule).map_err(|_| ParseError::InvalidSoundChange(rule.trim().to_string()))
+}
+
+/// Compile each sound change, with classes substituted
+///
+/// Changes which fail to compile are skipped, and the error is added to `errors`
+pub(super) fn compile_changes(
+    changes: &[RawChange],
+    classes: &Classes,
+    macros: &Macros,
+    backtrack_limit: Option<usize>,
+    errors: &mut Vec<Error>,
+) -> Vec<Change> {
+    changes
+        .iter()
+        .filter_map(|RawChange { rule, location }| {
+            match compile_allophone(rule, location, classes, macros, backtrack_limit) {
+                Ok(rewrite) => Some(Change {
+                    rule: rule.clone(),
+                    location: location.clone(),
+                    rewrite,
+                }),
+                Err(Error::Parse(ParseError::InvalidAllophone(_), location)) => {
+                    errors.push(Error::Parse(
+                        ParseError::InvalidSoundChange(rule.trim().to_string()),
+                        location,
+                    ));
+                    None
+                }
+                Err(err) => {
+                    errors.push(err);
+                    None
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,95 @@
+use super::*;
+use crate::{Draft, DraftBuilder};
+
+#[test]
+fn changes_are_parsed() {
+    let draft = Draft::from(
+        "
+        $V = [aeiou]
+        @features p t k -voice
+        @features b d g +voice
+        @change <[-voice]> > h / <V>_<V>
+        @change a > e / _i
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        draft.raw_changes,
+        [
+            RawChange {
+                rule: "<[-voice]> > h / <V>_<V>".to_string(),
+                location: draft.raw_changes[0].location.clone(),
+            },
+            RawChange {
+                rule: "a > e / _i".to_string(),
+                location: draft.raw_changes[1].location.clone(),
+            },
+        ]
+    );
+    assert_eq!(draft.raw_changes[1].location.line, 6);
+    assert_eq!(draft.changes.len(), 2);
+
+    // Changes are not allophones
+    assert!(draft.allophones.is_empty());
+    assert_eq!(draft.derive("ata").unwrap(), "ata");
+
+    let built = DraftBuilder::new()
+        .class("V", "[aeiou]")
+        .features(&["p", "t", "k"], &["-voice"])
+        .features(&["b", "d", "g"], &["+voice"])
+        .change("<[-voice]> > h / <V>_<V>")
+        .change("a > e / _i")
+        .build()
+        .unwrap();
+    assert_eq!(
+        built.evolve(&["ataki"], false).unwrap(),
+        draft.evolve(&["ataki"], false).unwrap()
+    );
+    assert_eq!(
+        draft.evolve(&["ataki"], false).unwrap().words[0].result,
+        "ahahi"
+    );
+}
+
+#[test]
+fn changes_are_converted_to_source() {
+    let draft = Draft::from("$V = [aeiou]; @change a > e / _<V>").unwrap();
+
+    let source = draft.to_source();
+    assert_eq!(source, "~<>\n\n@change a > e / _<V>\n\n$V = [aeiou]\n");
+
+    let parsed = Draft::from(&source).unwrap();
+    assert_eq!(parsed.raw_changes[0].rule, draft.raw_changes[0].rule);
+    assert_eq!(parsed.to_source(), source);
+}
+
+#[test]
+fn changes_return_error() {
+    let Err(Error::Multiple(errors)) =
+        Draft::from("@change a e\n@change <X> > e\n@change a > ∅ / _i_")
+    else {
+        panic!("Should return multiple errors");
+    };
+
+    assert!(matches!(
+        &errors[0],
+        Error::Parse(ParseError::InvalidSoundChange(rule), Location { line: 1, .. }) if rule == "a e"
+    ));
+    assert!(matches!(
+        &errors[1],
+        Error::Parse(ParseError::InvalidSoundChange(_), Location { line: 3, .. })
+    ));
+    assert!(matches!(
+        &errors[2],
+        Error::Parse(ParseError::ClassNotFound(name, _), Location { line: 2, span: Some(span), .. })
+            if name == "X" && *span == (8..11)
+    ));
+    assert_eq!(errors.len(), 3);
+
+    let draft = DraftBuilder::new().change("a > e > i").build();
+    assert!(matches!(
+        draft,
+        Err(Error::Parse(ParseError::InvalidSoundChange(_), _))
+    ));
+}
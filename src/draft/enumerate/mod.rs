@@ -0,0 +1,196 @@
+#[cfg(test)]
+mod tests;
+
+use fancy_regex_macro::regex;
+use regex_syntax::hir::{Class, Hir, HirKind, Literal, RepetitionKind::*, RepetitionRange::*};
+
+use super::Draft;
+use crate::error::Error;
+
+/// Maximum amount of segments which a class can match, to be enumerated
+pub const MAX_SEGMENTS: usize = 10_000;
+
+impl Draft {
+    /// Get every segment which a class matches, in order of the pattern, without duplicates
+    ///
+    /// The pattern of the class is parsed as a regex syntax tree, so ranges (`[a-z]`), escapes (`\u{e9}`),
+    /// alternations, and segment lists are enumerated correctly
+    ///
+    /// Fails if the class does not exist, or it is not finitely enumerable, such as if it contains
+    /// a repetition without a maximum (`+` or `*`), a look-around, an anchor, or a negated class.
+    /// Classes which match more than `MAX_SEGMENTS` segments (such as `.` or `\w`) are also not enumerable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::Draft;
+    /// let draft = Draft::from("$C = [p-t] | ng | \\u{e9}; $V = [^aeiou]").unwrap();
+    ///
+    /// assert_eq!(draft.enumerate_class("C").unwrap(), vec!["p", "q", "r", "s", "t", "ng", "é"]);
+    /// assert!(draft.enumerate_class("V").is_err());
+    /// ```
+    pub fn enumerate_class(&self, name: &str) -> Result<Vec<String>, Error> {
+        let Some((pattern, _)) = self.classes.get(name) else {
+            return Err(Error::MissingClass(name.to_string()));
+        };
+
+        let not_enumerable =
+            |reason: &str| Error::ClassNotEnumerable(name.to_string(), reason.to_string());
+
+        let segments = enumerate_pattern(pattern).map_err(not_enumerable)?;
+
+        if segments.is_empty() {
+            return Err(not_enumerable("it does not match any segments"));
+        }
+        Ok(segments)
+    }
+}
+
+/// Get every segment which a resolved pattern matches, in order, without duplicates or empty segments
+///
+/// Returns reason that the pattern is not enumerable, if it is not
+pub(super) fn enumerate_pattern(pattern: &str) -> Result<Vec<String>, &'static str> {
+    // Class of multiple-character segments inside brackets would be parsed as its individual characters
+    if has_group_in_brackets(pattern) {
+        return Err("it contains a class with multiple-character segments inside brackets, such as `[<S>]` with `$S = {ng}`");
+    }
+
+    // Atomic groups of segment lists are not supported, but do not change which segments match
+    let pattern = regex!(r"(?<!\\)\(\?>").replace_all(pattern, "(?:");
+
+    let hir = regex_syntax::Parser::new()
+        .parse(&pattern)
+        .map_err(|_| "it contains a look-around, backreference, or other unsupported syntax")?;
+
+    let mut segments = Vec::new();
+    for segment in enumerate(&hir)? {
+        if !segment.is_empty() && !segments.contains(&segment) {
+            segments.push(segment);
+        }
+    }
+    Ok(segments)
+}
+
+/// Check if pattern contains a group (such as a substituted class) inside a bracket class
+fn has_group_in_brackets(pattern: &str) -> bool {
+    let mut chars = pattern.chars().peekable();
+    // Amount of nested bracket classes
+    let mut depth = 0usize;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '[' => {
+                depth += 1;
+                // Closing bracket at start of class is literal
+                chars.next_if_eq(&'^');
+                chars.next_if_eq(&']');
+            }
+            ']' if depth > 0 => depth -= 1,
+            '(' if depth > 0 && chars.peek() == Some(&'?') => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// Get every string which a regex syntax tree matches, in order, with possible duplicates
+///
+/// Returns reason that the tree is not enumerable, if it is not
+fn enumerate(hir: &Hir) -> Result<Vec<String>, &'static str> {
+    let strings = match hir.kind() {
+        HirKind::Empty => vec![String::new()],
+
+        HirKind::Literal(Literal::Unicode(ch)) => vec![ch.to_string()],
+        HirKind::Literal(Literal::Byte(byte)) => vec![char::from(*byte).to_string()],
+
+        HirKind::Class(class) => {
+            let chars: Vec<char> = match class {
+                Class::Unicode(class) => class
+                    .iter()
+                    .flat_map(|range| range.start()..=range.end())
+                    .take(MAX_SEGMENTS + 1)
+                    .collect(),
+                Class::Bytes(class) => class
+                    .iter()
+                    .flat_map(|range| range.start()..=range.end())
+                    .map(char::from)
+                    .collect(),
+            };
+            chars.into_iter().map(String::from).collect()
+        }
+
+        HirKind::Anchor(_) | HirKind::WordBoundary(_) => {
+            return Err("it contains an anchor or word boundary, such as `^` or `\\b`");
+        }
+
+        HirKind::Group(group) => enumerate(&group.hir)?,
+
+        HirKind::Alternation(hirs) => {
+            let mut strings = Vec::new();
+            for hir in hirs {
+                strings.extend(enumerate(hir)?);
+                check_count(strings.len())?;
+            }
+            strings
+        }
+
+        HirKind::Concat(hirs) => {
+            let mut strings = vec![String::new()];
+            for hir in hirs {
+                strings = product(&strings, &enumerate(hir)?)?;
+            }
+            strings
+        }
+
+        HirKind::Repetition(repetition) => {
+            let (min, max) = match repetition.kind {
+                ZeroOrOne => (0, 1),
+                Range(Exactly(count)) => (count, count),
+                Range(Bounded(min, max)) => (min, max),
+                ZeroOrMore | OneOrMore | Range(AtLeast(_)) => {
+                    return Err("it contains a repetition without a maximum, such as `+` or `*`");
+                }
+            };
+
+            let item = enumerate(&repetition.hir)?;
+            let mut repeated = vec![String::new()];
+            let mut strings = Vec::new();
+            for count in 0..=max {
+                if count >= min {
+                    strings.extend(repeated.iter().cloned());
+                    check_count(strings.len())?;
+                }
+                repeated = product(&repeated, &item)?;
+            }
+            strings
+        }
+    };
+
+    check_count(strings.len())?;
+    Ok(strings)
+}
+
+/// Get every string of `first`, followed by every string of `second`
+fn product(first: &[String], second: &[String]) -> Result<Vec<String>, &'static str> {
+    check_count(first.len().saturating_mul(second.len()))?;
+
+    Ok(first
+        .iter()
+        .flat_map(|first| {
+            second
+                .iter()
+                .map(move |second| format!("{}{}", first, second))
+        })
+        .collect())
+}
+
+/// Fail if amount of strings is more than `MAX_SEGMENTS`
+fn check_count(count: usize) -> Result<(), &'static str> {
+    if count > MAX_SEGMENTS {
+        return Err("it matches too many segments, such as with `.`, `\\w`, or a negated class");
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use super::*;
+
+/// Enumerate class `_` of file
+fn enumerate_any(file: &str) -> Result<Vec<String>, Error> {
+    Draft::from(file).unwrap().enumerate_class("_")
+}
+
+/// Get reason that class `_` of file is not enumerable
+fn reason(file: &str) -> String {
+    match enumerate_any(file) {
+        Err(Error::ClassNotEnumerable(name, reason)) if name == "_" => reason,
+        other => panic!("Should not be enumerable, but was {:?}", other),
+    }
+}
+
+#[test]
+fn enumerate_class_works() {
+    assert_eq!(
+        enumerate_any("$_ = [a-e]").unwrap(),
+        vec!["a", "b", "c", "d", "e"]
+    );
+    assert_eq!(
+        enumerate_any(r"$_ = [pt] | \u{e9} | \x41 | \.").unwrap(),
+        vec!["p", "t", "é", "A", "."]
+    );
+    assert_eq!(
+        enumerate_any("$C = [ptk]; $V = [ae]; $_ = <C> | <V> | <C>").unwrap(),
+        vec!["k", "p", "t", "a", "e"]
+    );
+    assert_eq!(
+        enumerate_any("$_ = [pt]h? | s{2}").unwrap(),
+        vec!["p", "ph", "t", "th", "ss"]
+    );
+    assert_eq!(
+        enumerate_any("$S = {a ng}; $_ = <S> | (?i)b").unwrap(),
+        vec!["ng", "a", "B", "b"]
+    );
+    assert_eq!(enumerate_any("$_ = (?:a{1,2})").unwrap(), vec!["a", "aa"]);
+    // Classes inside brackets are a union
+    assert_eq!(
+        enumerate_any("$C = [pt]; $V = [ae]; $_ = [<C><V>]").unwrap(),
+        vec!["a", "e", "p", "t"]
+    );
+    assert_eq!(
+        enumerate_any("$S = {a b}; $C = <S>; $_ = [x<C>]").unwrap(),
+        vec!["a", "b", "x"]
+    );
+    assert_eq!(
+        enumerate_any(r"$_ = []()] | [\[(]").unwrap(),
+        vec!["(", ")", "]", "["]
+    );
+
+    assert!(matches!(
+        Draft::from("$C = a").unwrap().enumerate_class("V"),
+        Err(Error::MissingClass(name)) if name == "V"
+    ));
+}
+
+#[test]
+fn enumerate_class_returns_error() {
+    assert!(reason("$_ = [ptk]+").contains("repetition"));
+    assert!(reason("$_ = a*").contains("repetition"));
+    assert!(reason("$_ = a{2,}").contains("repetition"));
+    assert!(reason("$_ = ^a").contains("anchor"));
+    assert!(reason(r"$_ = a\b").contains("anchor"));
+    assert!(reason("$_ = (?=a)a").contains("look-around"));
+    assert!(reason(r"$_ = (a)\1").contains("look-around"));
+    assert!(reason("$_ = [^aeiou]").contains("too many"));
+    assert!(reason("$_ = .").contains("too many"));
+    assert!(reason(r"$_ = \w").contains("too many"));
+    assert!(reason("$_ = [a-z]{4}").contains("too many"));
+    assert!(reason("$_ = a{0}").contains("any segments"));
+    assert!(reason("$S = {a ng}; $_ = [x<S>]").contains("inside brackets"));
+}
@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, fmt};
+
+use fancy_regex_macro::regex;
+
+use super::{
+    allophony::RawAllophone, changes::RawChange, macros::Macros, replace::class_references,
+    segments::segments_pattern, Classes, RawRule,
+};
+use crate::{
+    error::{closest_name, Error, Location, ParseError},
+    REGEX_MATCH_FAIL,
+};
+
+/// Features given to segments, with a single `@features` directive
+///
+/// Features of a segment in a later row override features with the same name in an earlier row
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct FeatureRow {
+    /// Segments which the features are given to
+    pub segments: Vec<String>,
+    /// Name of each feature, with `true` for `+` or `false` for `-`
+    pub features: Vec<(bool, String)>,
+    /// Location of statement
+    pub location: Location,
+}
+
+impl fmt::Display for FeatureRow {
+    /// Convert row to `@features` directive
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@features {}", self.segments.join(" "))?;
+        for (value, name) in &self.features {
+            write!(f, " {}{}", if *value { '+' } else { '-' }, name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse features, such as `+voice` or `-nasal`
+///
+/// Returns the first invalid feature, if any
+pub(super) fn parse_features<'a>(features: &[&'a str]) -> Result<Vec<(bool, String)>, &'a str> {
+    features
+        .iter()
+        .map(|feature| {
+            if !regex!(r"^[+-]\w+$")
+                .is_match(feature)
+                .expect(REGEX_MATCH_FAIL)
+            {
+                return Err(*feature);
+            }
+            Ok((feature.starts_with('+'), feature[1..].to_string()))
+        })
+        .collect()
+}
+
+/// Resolve every feature bundle (such as `<[+voice -nasal]>`) which is referenced in classes, class macros, rules, allophones, and sound changes
+///
+/// Each bundle is resolved as a class, with a pattern of every segment which has all features of the bundle,
+/// so it can be substituted the same as a class (see `resolve_classes`)
+///
+/// Bundles which fail to resolve are given an empty value, and the error is added to `errors`
+pub(super) fn resolve_bundles(
+    rows: &[FeatureRow],
+    raw_classes: &Classes,
+    macros: &Macros,
+    raw_rules: &[RawRule],
+    allophones: &[RawAllophone],
+    changes: &[RawChange],
+    errors: &mut Vec<Error>,
+) -> Classes {
+    let mut resolved = Classes::new();
+
+    // Patterns of classes and class macros in order of definition, then rules, allophones, and sound changes, for consistent order of errors
+    let mut classes: Vec<_> = raw_classes
+        .values()
+        .map(|(pattern, location)| (pattern, location))
+        .chain(
+            macros
+                .values()
+                .map(|class_macro| (&class_macro.pattern, &class_macro.location)),
+        )
+        .collect();
+    classes.sort_by_key(|(_, location)| (location.file.clone(), location.offset));
+    let patterns = classes
+        .into_iter()
+        .chain(raw_rules.iter().map(|rule| (&rule.pattern, &rule.location)))
+        .chain(
+            allophones
+                .iter()
+                .map(|allophone| (&allophone.rule, &allophone.location)),
+        )
+        .chain(
+            changes
+                .iter()
+                .map(|change| (&change.rule, &change.location)),
+        );
+
+    for (pattern, location) in patterns {
+        for (name, span) in class_references(pattern) {
+            // Only resolve bundles, each once
+            if !name.starts_with('[') || resolved.contains_key(&name) {
+                continue;
+            }
+
+            let value = match resolve_bundle(&name, rows) {
+                Ok(value) => value,
+                Err(kind) => {
+                    errors.push(Error::Parse(kind, location.sub_span(span)));
+                    String::from("(?:)")
+                }
+            };
+            resolved.insert(name, (value, location.clone()));
+        }
+    }
+
+    resolved
+}
+
+/// Resolve a single feature bundle, such as `[+voice-nasal]`, with spaces removed
+fn resolve_bundle(bundle: &str, rows: &[FeatureRow]) -> Result<String, ParseError> {
+    // Split features, which are not separated once spaces are removed
+    let inner = bundle
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .unwrap_or_default()
+        .replace('+', " +")
+        .replace('-', " -");
+    let words: Vec<_> = inner.split_whitespace().collect();
+
+    let features = match parse_features(&words) {
+        Ok(features) if !features.is_empty() => features,
+        _ => return Err(ParseError::InvalidFeatureBundle(bundle.to_string())),
+    };
+
+    // Features of each segment, in order of first definition
+    let mut segments: Vec<(&str, HashMap<&str, bool>)> = Vec::new();
+    for row in rows {
+        for segment in &row.segments {
+            let index = match segments.iter().position(|(other, _)| other == segment) {
+                Some(index) => index,
+                None => {
+                    segments.push((segment, HashMap::new()));
+                    segments.len() - 1
+                }
+            };
+            for (value, name) in &row.features {
+                segments[index].1.insert(name, *value);
+            }
+        }
+    }
+
+    // Every feature must be given to some segment
+    for (_, name) in &features {
+        if !segments
+            .iter()
+            .any(|(_, values)| values.contains_key(name.as_str()))
+        {
+            let names = rows
+                .iter()
+                .flat_map(|row| row.features.iter().map(|(_, name)| name.as_str()));
+            let closest = closest_name(name, names);
+            return Err(ParseError::FeatureNotFound(
+                name.clone(),
+                closest.map(str::to_string),
+            ));
+        }
+    }
+
+    let selected: Vec<String> = segments
+        .into_iter()
+        .filter(|(_, values)| {
+            features
+                .iter()
+                .all(|(value, name)| values.get(name.as_str()) == Some(value))
+        })
+        .map(|(segment, _)| segment.to_string())
+        .collect();
+
+    if selected.is_empty() {
+        let bundle = format!("[{}]", words.join(" "));
+        return Err(ParseError::EmptyFeatureBundle(bundle));
+    }
+
+    Ok(format!("(?:{})", segments_pattern(selected, &[])))
+}
@@ -0,0 +1,143 @@
+use super::*;
+use crate::{Draft, DraftBuilder};
+
+/// Features of stops and nasals
+const FEATURES: &str = "
+    @features p t k -voice -nasal
+    @features b d g +voice -nasal
+    @features m n ng +voice +nasal
+";
+
+#[test]
+fn parse_features_works() {
+    assert_eq!(
+        parse_features(&["+voice", "-nasal"]),
+        Ok(vec![
+            (true, "voice".to_string()),
+            (false, "nasal".to_string())
+        ])
+    );
+    assert_eq!(parse_features(&["+voice", "nasal"]), Err("nasal"));
+    assert_eq!(parse_features(&["+"]), Err("+"));
+    assert_eq!(parse_features(&["+a-b"]), Err("+a-b"));
+}
+
+#[test]
+fn feature_row_to_string_works() {
+    let row = FeatureRow {
+        segments: vec!["p".to_string(), "ng".to_string()],
+        features: vec![(false, "voice".to_string()), (true, "nasal".to_string())],
+        location: Default::default(),
+    };
+    assert_eq!(row.to_string(), "@features p ng -voice +nasal");
+}
+
+#[test]
+fn resolve_bundle_works() {
+    let draft = Draft::from(FEATURES).unwrap();
+    let rows = &draft.features;
+
+    assert_eq!(
+        resolve_bundle("[+voice]", rows).unwrap(),
+        "(?:(?>ng|b|d|g|m|n))"
+    );
+    assert_eq!(
+        resolve_bundle("[+voice-nasal]", rows).unwrap(),
+        "(?:(?>b|d|g))"
+    );
+    assert_eq!(resolve_bundle("[-voice]", rows).unwrap(), "(?:(?>p|t|k))");
+
+    assert!(matches!(
+        resolve_bundle("[+voice+nasal-voice]", rows),
+        Err(ParseError::EmptyFeatureBundle(bundle)) if bundle == "[+voice +nasal -voice]"
+    ));
+    assert!(matches!(
+        resolve_bundle("[+voise]", rows),
+        Err(ParseError::FeatureNotFound(name, Some(closest))) if name == "voise" && closest == "voice"
+    ));
+    assert!(matches!(
+        resolve_bundle("[]", rows),
+        Err(ParseError::InvalidFeatureBundle(_))
+    ));
+    assert!(matches!(
+        resolve_bundle("[voice]", rows),
+        Err(ParseError::InvalidFeatureBundle(_))
+    ));
+}
+
+#[test]
+fn later_features_override_earlier() {
+    let draft = Draft::from("@features p t -voice; @features t +voice +coronal").unwrap();
+    let rows = &draft.features;
+
+    assert_eq!(resolve_bundle("[-voice]", rows).unwrap(), "(?:(?>p))");
+    assert_eq!(resolve_bundle("[+voice]", rows).unwrap(), "(?:(?>t))");
+}
+
+#[test]
+fn bundles_in_classes_and_rules_work() {
+    let draft = Draft::from(&format!(
+        "{}
+        $Nasal = <[+nasal]>
+        $_ = <[-nasal]> | <Nasal> | [aeiou]
+        + ^ <_>+ $
+        ! <[ +voice  -nasal ]> $
+        ?+ ngato mapa
+        ?! pab xa",
+        FEATURES
+    ))
+    .unwrap();
+
+    assert_eq!(draft.run().fail_count, 0);
+    assert_eq!(draft.classes["[+voice-nasal]"].0, "(?:(?>b|d|g))");
+
+    let draft = DraftBuilder::new()
+        .features(&["s", "z"], &["+fricative"])
+        .features(&["z"], &["+voice"])
+        .rule(false, "<[+fricative +voice]>")
+        .test(true, "sa")
+        .test(false, "za")
+        .build()
+        .unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+}
+
+#[test]
+fn bundles_return_error() {
+    let file = format!("{}\n$S = <[+voise]>\n+ <[+voice +nasal -voice]>", FEATURES);
+    let Err(Error::Multiple(errors)) = Draft::from(&file) else {
+        panic!("Should return multiple errors");
+    };
+
+    assert!(matches!(
+        &errors[0],
+        Error::Parse(ParseError::FeatureNotFound(name, _), Location { line: 6, span: Some(span), .. })
+            if name == "voise" && *span == (5..15)
+    ));
+    assert!(matches!(
+        &errors[1],
+        Error::Parse(ParseError::EmptyFeatureBundle(_), Location { line: 7, .. })
+    ));
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn features_directive_returns_error() {
+    assert!(matches!(
+        Draft::from("@features -voice"),
+        Err(Error::Parse(ParseError::NoFeatureSegments, _))
+    ));
+    assert!(matches!(
+        Draft::from("@features p t"),
+        Err(Error::Parse(ParseError::NoFeatures, _))
+    ));
+    assert!(matches!(
+        Draft::from("@features p -voice nasal"),
+        Err(Error::Parse(ParseError::InvalidFeature(feature), Location { span: Some(span), .. }))
+            if feature == "nasal" && span == (19..24)
+    ));
+    assert!(matches!(
+        DraftBuilder::new().features(&["p"], &["voice"]).build(),
+        Err(Error::Parse(ParseError::InvalidFeature(feature), _)) if feature == "voice"
+    ));
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::statements::{span_of, split_statements};
+use crate::error::{Error, Location, ParseError};
+
+/// Statement text, with location in source file
+pub(super) type Statement = (String, Location);
+
+/// Split file into list of statements, with `@include` directives replaced by the statements of the included file
+///
+/// Included paths are relative to the directory of `path`
+///
+/// `stack` holds the canonical paths of every file currently being included, to detect cyclic includes.
+/// `loaded` holds the canonical paths of every file already included, so each file is only included once
+///
+/// Invalid include directives are skipped, and the error is added to `errors`
+pub(super) fn load_statements(
+    file: &str,
+    path: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+    loaded: &mut Vec<PathBuf>,
+    errors: &mut Vec<Error>,
+) -> Vec<Statement> {
+    let mut statements = Vec::new();
+
+    for (statement, line, offset) in split_statements(file) {
+        let location = Location {
+            file: path.map(Path::to_path_buf),
+            line,
+            offset,
+            span: None,
+        };
+
+        // Pass through any statement which is not an include directive
+        let Some(("include", include)) = split_directive(&statement) else {
+            statements.push((statement, location));
+            continue;
+        };
+
+        match include_file(include, path, stack, loaded, errors) {
+            Ok(included) => statements.extend(included),
+
+            Err(kind) => {
+                // Use span of path, or entire statement if path is empty
+                let span = if include.is_empty() {
+                    span_of(&statement, statement.trim())
+                } else {
+                    span_of(&statement, include)
+                };
+
+                errors.push(Error::Parse(kind, location.with_span(span)));
+            }
+        }
+    }
+
+    statements
+}
+
+/// Read and split included file into statements (recursively)
+///
+/// `include` is the path given in the include directive, `path` is the path of the file which includes it
+///
+/// Files which were already included are skipped, so a file included by multiple files does not redefine its classes
+fn include_file(
+    include: &str,
+    path: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+    loaded: &mut Vec<PathBuf>,
+    errors: &mut Vec<Error>,
+) -> Result<Vec<Statement>, ParseError> {
+    if include.is_empty() {
+        return Err(ParseError::NoIncludePath);
+    }
+
+    // Included files can only be resolved relative to another file
+    let Some(path) = path else {
+        return Err(ParseError::IncludeWithoutPath);
+    };
+
+    // Resolve path relative to current file
+    let include = match path.parent() {
+        Some(parent) => parent.join(include),
+        None => PathBuf::from(include),
+    };
+
+    // Read included file
+    let file = match fs::read_to_string(&include) {
+        Ok(file) => file,
+        Err(err) => return Err(ParseError::IncludeReadFail(include, err)),
+    };
+
+    // Check that file is not already being included
+    let canonical = include.canonicalize().unwrap_or_else(|_| include.clone());
+    if stack.contains(&canonical) {
+        return Err(ParseError::CyclicInclude(include));
+    }
+
+    // Skip file if already included
+    if loaded.contains(&canonical) {
+        return Ok(Vec::new());
+    }
+    loaded.push(canonical.clone());
+
+    // Get statements of included file (recursively)
+    stack.push(canonical);
+    let statements = load_statements(&file, Some(&include), stack, loaded, errors);
+    stack.pop();
+
+    Ok(statements)
+}
+
+/// Split directive statement (beginning with `@`) into name and argument
+///
+/// Returns `None` if statement is not a directive
+pub(super) fn split_directive(statement: &str) -> Option<(&str, &str)> {
+    let directive = statement.trim().strip_prefix('@')?;
+
+    Some(match directive.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (directive, &directive[directive.len()..]),
+    })
+}
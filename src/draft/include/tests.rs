@@ -0,0 +1,179 @@
+use std::env;
+
+use crate::{error::ParseError, Draft};
+
+use super::*;
+
+/// Directory for test files, which is removed when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Get path of file in directory
+    fn join(&self, path: &str) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Create a new empty directory for test files
+fn temp_dir(name: &str) -> TempDir {
+    let dir = env::temp_dir().join(format!("phonet-include-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    TempDir(dir)
+}
+
+#[test]
+fn split_directive_works() {
+    assert_eq!(
+        split_directive("@include a.phonet"),
+        Some(("include", "a.phonet"))
+    );
+    assert_eq!(
+        split_directive("  @include   a b  "),
+        Some(("include", "a b"))
+    );
+    assert_eq!(split_directive("@include"), Some(("include", "")));
+    assert_eq!(split_directive("@"), Some(("", "")));
+    assert_eq!(split_directive("$_ = a"), None);
+}
+
+#[test]
+fn load_statements_works() {
+    let dir = temp_dir("load");
+
+    fs::write(dir.join("classes.phonet"), "$C = [ptk]\n$V = [aeiou]").unwrap();
+    fs::write(
+        dir.join("main.phonet"),
+        "~<>\n@include classes.phonet\n+ ^<C><V>$",
+    )
+    .unwrap();
+
+    let path = dir.join("main.phonet");
+    let file = fs::read_to_string(&path).unwrap();
+    let statements = load_statements(
+        &file,
+        Some(&path),
+        &mut Vec::new(),
+        &mut Vec::new(),
+        &mut Vec::new(),
+    );
+
+    let statements: Vec<_> = statements
+        .into_iter()
+        .map(|(statement, location)| {
+            (
+                statement,
+                location.line,
+                location.file.unwrap().file_name().unwrap().to_owned(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        statements,
+        vec![
+            ("~<>".to_string(), 1, "main.phonet".into()),
+            ("$C = [ptk]".to_string(), 1, "classes.phonet".into()),
+            ("$V = [aeiou]".to_string(), 2, "classes.phonet".into()),
+            ("+ ^<C><V>$".to_string(), 3, "main.phonet".into()),
+        ]
+    );
+}
+
+#[test]
+fn load_statements_returns_error() {
+    let dir = temp_dir("error");
+
+    // Cyclic include
+    fs::write(dir.join("a.phonet"), "@include b.phonet").unwrap();
+    fs::write(dir.join("b.phonet"), "$C = [ptk]\n@include a.phonet").unwrap();
+
+    assert!(matches!(
+        Draft::from_path(dir.join("a.phonet")),
+        Err(Error::Parse(ParseError::CyclicInclude(..), Location { line: 2, file: Some(file), .. }))
+            if file.ends_with("b.phonet")
+    ));
+
+    // Missing file
+    fs::write(dir.join("c.phonet"), "\n@include missing.phonet").unwrap();
+
+    assert!(matches!(
+        Draft::from_path(dir.join("c.phonet")),
+        Err(Error::Parse(
+            ParseError::IncludeReadFail(..),
+            Location { line: 2, .. }
+        ))
+    ));
+
+    // No path
+    assert!(matches!(
+        Draft::from_path(dir.join("a.phonet").with_file_name("d.phonet")),
+        Err(Error::ReadFile(..))
+    ));
+    fs::write(dir.join("d.phonet"), "@include").unwrap();
+    assert!(matches!(
+        Draft::from_path(dir.join("d.phonet")),
+        Err(Error::Parse(ParseError::NoIncludePath, _))
+    ));
+
+    // Parsed from string
+    assert!(matches!(
+        Draft::from("@include a.phonet"),
+        Err(Error::Parse(ParseError::IncludeWithoutPath, _))
+    ));
+}
+
+#[test]
+fn included_draft_works() {
+    let dir = temp_dir("draft");
+
+    fs::write(
+        dir.join("base.phonet"),
+        "$C = [ptk]\n$V = [aeiou]\n$_ = <C>|<V>\n+ ^<_>+$",
+    )
+    .unwrap();
+    fs::create_dir_all(dir.join("dialect")).unwrap();
+    fs::write(
+        dir.join("dialect/main.phonet"),
+        "~<>\n@include ../base.phonet\n! <V><V>\n?+ tako\n?! taako",
+    )
+    .unwrap();
+
+    let draft = Draft::from_path(dir.join("dialect/main.phonet")).unwrap();
+
+    assert_eq!(draft.rules.len(), 2);
+    assert_eq!(draft.test_count, 2);
+    assert_eq!(draft.run().fail_count, 0);
+
+    // Minified file is self-contained
+    let minified = draft.minify(true).unwrap();
+    assert_eq!(
+        minified,
+        "~<>;+^(?:(?:[ptk])|(?:[aeiou]))+$;!(?:[aeiou])(?:[aeiou]);?+tako;?!taako"
+    );
+    assert_eq!(Draft::from(&minified).unwrap().run().fail_count, 0);
+
+    // File included by multiple files is only included once
+    fs::write(dir.join("base.phonet"), "$C = [ptk]").unwrap();
+    fs::write(dir.join("left.phonet"), "@include base.phonet\n$L = <C>l").unwrap();
+    fs::write(dir.join("right.phonet"), "@include base.phonet\n$R = <C>r").unwrap();
+    fs::write(
+        dir.join("diamond.phonet"),
+        "@include left.phonet\n@include right.phonet\n+ ^(<L>|<R>)$",
+    )
+    .unwrap();
+
+    let draft = Draft::from_path(dir.join("diamond.phonet")).unwrap();
+    assert_eq!(draft.raw_classes.len(), 3);
+
+    // Unknown directive
+    assert!(matches!(
+        Draft::from("@import something"),
+        Err(Error::Parse(ParseError::UnknownDirective(name), _)) if name == "import"
+    ));
+}
@@ -0,0 +1,194 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use fancy_regex_macro::regex;
+
+use super::{
+    replace::{class_references, replace_classes},
+    replace_angle_brackets, Classes,
+};
+use crate::{
+    error::{Error, Location, ParseError},
+    REGEX_MATCH_FAIL,
+};
+
+/// Maps names of class macros to their definition
+pub(crate) type Macros = HashMap<String, ClassMacro>;
+
+/// Class with parameters, such as `$Geminate(x) = <x><x>`
+///
+/// Invoked with class names as arguments, such as `<Geminate(C)>`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct ClassMacro {
+    /// Names of parameters, which are used as classes in the pattern
+    pub parameters: Vec<String>,
+    /// Pattern, as written in file
+    pub pattern: String,
+    /// Location of statement
+    pub location: Location,
+}
+
+/// Split name of class definition into name and parameters, if it has parameters, such as `Geminate(x, y)`
+pub(super) fn split_parameters(name: &str) -> Result<(&str, Option<Vec<String>>), ParseError> {
+    let Some((name, parameters)) = name.strip_suffix(')').and_then(|name| name.split_once('('))
+    else {
+        return Ok((name, None));
+    };
+
+    let mut list: Vec<String> = Vec::new();
+    for parameter in parameters.split(',').map(str::trim) {
+        if !regex!(r"^\w+$")
+            .is_match(parameter)
+            .expect(REGEX_MATCH_FAIL)
+        {
+            return Err(ParseError::InvalidMacroParameter(parameter.to_string()));
+        }
+        if list.iter().any(|other| other == parameter) {
+            return Err(ParseError::DuplicateMacroParameter(parameter.to_string()));
+        }
+        list.push(parameter.to_string());
+    }
+
+    Ok((name.trim_end(), Some(list)))
+}
+
+/// Split class reference into name and arguments, if it is an invocation of a class macro, such as `Geminate(C)`
+///
+/// Spaces should already be removed
+pub(super) fn split_call(reference: &str) -> Option<(&str, Vec<&str>)> {
+    let (name, arguments) = reference.strip_suffix(')')?.split_once('(')?;
+
+    let arguments = if arguments.is_empty() {
+        Vec::new()
+    } else {
+        arguments.split(',').collect()
+    };
+    Some((name, arguments))
+}
+
+/// Replace references to parameters in pattern of class macro with arguments
+///
+/// Parameters which are used as arguments of another class macro are also replaced.
+/// Spaces are removed, and `<` and `>` are replaced with `⟨` and `⟩`, where classes are
+pub(super) fn substitute_parameters(
+    pattern: &str,
+    parameters: &[String],
+    arguments: &[&str],
+) -> String {
+    let substitute = |name: &str| -> String {
+        match parameters.iter().position(|parameter| parameter == name) {
+            Some(index) => arguments[index].to_string(),
+            None => name.to_string(),
+        }
+    };
+
+    let pattern = replace_angle_brackets(&pattern.replace(' ', ""));
+
+    let mut output = String::new();
+    // Class reference which is being built
+    let mut reference: Option<String> = None;
+
+    for ch in pattern.chars() {
+        match (ch, &mut reference) {
+            ('⟨', _) => {
+                // Unfinished reference is kept as it was
+                if let Some(unfinished) = reference.replace(String::new()) {
+                    output.push('⟨');
+                    output.push_str(&unfinished);
+                }
+            }
+
+            ('⟩', Some(name)) => {
+                let name = match split_call(name) {
+                    Some((name, arguments)) => format!(
+                        "{}({})",
+                        name,
+                        arguments
+                            .into_iter()
+                            .map(substitute)
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    ),
+                    None => substitute(name),
+                };
+                output.push_str(&format!("⟨{}⟩", name));
+                reference = None;
+            }
+
+            (_, Some(name)) => name.push(ch),
+            (_, None) => output.push(ch),
+        }
+    }
+
+    if let Some(unfinished) = reference {
+        output.push('⟨');
+        output.push_str(&unfinished);
+    }
+
+    output
+}
+
+/// Get names of every class used by a class reference
+///
+/// For an invocation of a class macro, this is every argument, and every class used by the pattern of the macro (recursively)
+///
+/// `visited` holds the names of every class macro which has been checked, for recursive macros
+pub(super) fn referenced_classes(
+    reference: &str,
+    macros: &Macros,
+    visited: &mut Vec<String>,
+) -> Vec<String> {
+    let Some((name, arguments)) = split_call(reference) else {
+        return vec![reference.to_string()];
+    };
+
+    let mut names: Vec<String> = arguments.iter().map(|name| name.to_string()).collect();
+
+    if let Some(class_macro) = macros.get(name) {
+        if !visited.iter().any(|other| other == name) {
+            visited.push(name.to_string());
+
+            for (reference, _) in class_references(&class_macro.pattern) {
+                if !class_macro.parameters.contains(&reference) {
+                    names.extend(referenced_classes(&reference, macros, visited));
+                }
+            }
+        }
+    }
+
+    names
+}
+
+/// Check that the pattern of every class macro is valid, without arguments
+///
+/// Each parameter is substituted with an empty class.
+/// Errors are added to `errors`
+pub(super) fn check_macros(macros: &Macros, classes: &Classes, errors: &mut Vec<Error>) {
+    // Check in order of definition, for consistent order of errors
+    let mut list: Vec<_> = macros.values().collect();
+    list.sort_by_key(|class_macro| {
+        (
+            class_macro.location.file.clone(),
+            class_macro.location.offset,
+        )
+    });
+
+    for ClassMacro {
+        parameters,
+        pattern,
+        location,
+    } in list
+    {
+        let mut classes = classes.clone();
+        for parameter in parameters {
+            classes.insert(parameter.clone(), (String::from("(?:)"), location.clone()));
+        }
+
+        if let Err(err) = replace_classes(pattern, &classes, macros, location) {
+            errors.push(err);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use super::*;
+use crate::{Draft, DraftBuilder};
+
+#[test]
+fn split_parameters_works() {
+    assert!(matches!(split_parameters("C"), Ok(("C", None))));
+    assert!(matches!(
+        split_parameters("Geminate(x)"),
+        Ok(("Geminate", Some(parameters))) if parameters == ["x"]
+    ));
+    assert!(matches!(
+        split_parameters("Between ( a , b )"),
+        Ok(("Between", Some(parameters))) if parameters == ["a", "b"]
+    ));
+
+    assert!(matches!(
+        split_parameters("F()"),
+        Err(ParseError::InvalidMacroParameter(name)) if name.is_empty()
+    ));
+    assert!(matches!(
+        split_parameters("F(x, <y>)"),
+        Err(ParseError::InvalidMacroParameter(name)) if name == "<y>"
+    ));
+    assert!(matches!(
+        split_parameters("F(x, x)"),
+        Err(ParseError::DuplicateMacroParameter(name)) if name == "x"
+    ));
+}
+
+#[test]
+fn split_call_works() {
+    assert_eq!(split_call("C"), None);
+    assert_eq!(split_call("Geminate(C)"), Some(("Geminate", vec!["C"])));
+    assert_eq!(split_call("F(a,b)"), Some(("F", vec!["a", "b"])));
+    assert_eq!(split_call("F()"), Some(("F", vec![])));
+}
+
+#[test]
+fn substitute_parameters_works() {
+    let parameters = ["x".to_string(), "y".to_string()];
+
+    assert_eq!(
+        substitute_parameters("<x> <y> <z>", &parameters, &["C", "V"]),
+        "⟨C⟩⟨V⟩⟨z⟩"
+    );
+    assert_eq!(
+        substitute_parameters("<F(x, z)>+ (?<x>a)", &parameters, &["C", "V"]),
+        "⟨F(C,z)⟩+(?<x>a)"
+    );
+    assert_eq!(
+        substitute_parameters("<y> ⟨x", &parameters, &["C", "V"]),
+        "⟨V⟩⟨x"
+    );
+}
+
+#[test]
+fn referenced_classes_works() {
+    let draft =
+        Draft::from("$F(x) = <x> <V> <G(x, C)>; $G(a, b) = <a> <b> <N>; $V = a; $C = b; $N = c")
+            .unwrap();
+
+    assert_eq!(
+        referenced_classes("A", &draft.macros, &mut Vec::new()),
+        vec!["A"]
+    );
+    assert_eq!(
+        referenced_classes("F(A)", &draft.macros, &mut Vec::new()),
+        vec!["A", "V", "x", "C", "N"]
+    );
+}
+
+#[test]
+fn macros_work() {
+    let draft = Draft::from(
+        "
+        $C = [ptk]
+        $V = [aeiou]
+        $Geminate(x) = <x><x>
+        $Between(x, y) = (?<=<x>) <y> (?=<x>)
+        $Cluster(x) = <Geminate(x)> <C>
+        ! <Geminate(V)>
+        ! <Between(V, Geminate_C)>
+        ! <Cluster(C)>
+        $Geminate_C = <Geminate(C)>
+        ?+ kato ttaka
+        ?! kaato katto akkta
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(draft.run().fail_count, 0);
+    assert_eq!(
+        draft.rules[0].pattern.as_str(),
+        "(?:(?:[aeiou])(?:[aeiou]))"
+    );
+    assert_eq!(draft.classes["Geminate_C"].0, "(?:(?:(?:[ptk])(?:[ptk])))");
+
+    // Parameters shadow classes
+    let draft = Draft::from("$x = a; $F(x) = <x>; + ^<F(V)>$; $V = b; ?+ b; ?! a").unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+
+    let draft = DraftBuilder::new()
+        .class("V", "[aeiou]")
+        .class("Geminate(x)", "<x><x>")
+        .rule(false, "<Geminate(V)>")
+        .test(true, "kato")
+        .test(false, "kaato")
+        .build()
+        .unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+}
+
+#[test]
+fn macros_are_minified_and_converted_to_source() {
+    let draft = Draft::from("$V = [aeiou]; $Geminate( x ) = <x><x>; ! <Geminate(V)>").unwrap();
+
+    assert_eq!(
+        draft.minify(false).unwrap(),
+        "~<>;!(?:(?:[aeiou])(?:[aeiou]))"
+    );
+    assert_eq!(
+        draft.to_source(),
+        "~<>\n\n$V           = [aeiou]\n$Geminate(x) = <x><x>\n\n! <Geminate(V)>\n"
+    );
+}
+
+#[test]
+fn macros_return_error() {
+    use ParseError::*;
+
+    let error = |file: &str| match Draft::from(file) {
+        Err(Error::Parse(kind, location)) => (kind, location.span),
+        Err(err) => panic!("Should be a single error, not {:?}", err),
+        Ok(_) => panic!("Should be an error"),
+    };
+
+    assert!(matches!(
+        error("$F(x) = <x>; + a <F>"),
+        (ClassArgumentCount(name, 1, 0), Some(span)) if name == "F" && span == (5..8)
+    ));
+    assert!(matches!(
+        error("$F(x) = <x>; + <F(A, B)>"),
+        (ClassArgumentCount(name, 1, 2), Some(span)) if name == "F" && span == (3..12)
+    ));
+    assert!(matches!(
+        error("$C = a; + <C(A)>"),
+        (ClassArgumentCount(name, 0, 1), Some(span)) if name == "C" && span == (3..9)
+    ));
+    assert!(matches!(
+        error("$C = a; $F(x) = <x>; + <F([ab])>"),
+        (InvalidClassArgument(argument), Some(span)) if argument == "[ab]" && span == (3..12)
+    ));
+    assert!(matches!(
+        error("$C = a; + <G(C)>"),
+        (ClassNotFound(name, _), Some(span)) if name == "G" && span == (3..9)
+    ));
+    assert!(matches!(
+        error("$F(x) = <x>; $F = a"),
+        (ClassAlreadyExists(name), _) if name == "F"
+    ));
+    assert!(matches!(
+        error("$F(x, y-z) = <x>"),
+        (InvalidMacroParameter(name), _) if name == "y-z"
+    ));
+
+    // Pattern of macro is checked, even if it is not used
+    assert!(matches!(
+        error("$F(x) = <x> <Missing>"),
+        (ClassNotFound(name, None), Some(span)) if name == "Missing" && span == (12..21)
+    ));
+    assert!(matches!(
+        Draft::from("$F(x) = <G(x)>; $G(x) = <F(x)>"),
+        Err(Error::Multiple(errors)) if matches!(
+            &errors[0],
+            Error::Parse(CyclicMacro(name), _) if name == "G"
+        )
+    ));
+}
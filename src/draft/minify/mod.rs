@@ -4,6 +4,7 @@ mod tests;
 use crate::error::Error;
 
 use super::{
+    macros::Macros,
     replace::replace_classes,
     Classes,
     Message::{self, *},
@@ -15,19 +16,21 @@ use super::{
 /// If `with_tests` is true, the minified string will include tests
 pub(super) fn minify(
     mode: Mode,
+    backtrack_limit: Option<usize>,
     classes: &Classes,
+    macros: &Macros,
     rules: &[RawRule],
     messages: &[Message<TestDraft>],
     with_tests: bool,
 ) -> Result<String, Error> {
     let (positive, negative) = minify_tests(messages);
 
-    // Include mode and rules
-    let mut minified = format!(
-        "~{mode};{rules}",
-        mode = mode.as_str(),
-        rules = minify_rules(rules, classes)?.join(";"),
-    );
+    // Include mode, backtrack limit, and rules
+    let mut minified = format!("~{};", mode.as_str());
+    if let Some(limit) = backtrack_limit {
+        minified += &format!("@backtrack-limit {};", limit);
+    }
+    minified += &minify_rules(rules, classes, macros)?.join(";");
 
     // If tests are enabled
     if with_tests {
@@ -44,12 +47,14 @@ pub(super) fn minify(
 }
 
 /// Minify tests, separate positive and negative intents
+///
+/// Expected reasons and transcriptions of tests are not included, as notes and mappings are not included in minified file
 fn minify_tests(messages: &[Message<TestDraft>]) -> (Vec<&str>, Vec<&str>) {
     let mut positive = Vec::new();
     let mut negative = Vec::new();
 
     for msg in messages {
-        if let Test(TestDraft { word, intent }) = msg {
+        if let Test(TestDraft { word, intent, .. }) = msg {
             if *intent {
                 positive.push(word.as_str());
             } else {
@@ -62,20 +67,24 @@ fn minify_tests(messages: &[Message<TestDraft>]) -> (Vec<&str>, Vec<&str>) {
 }
 
 /// Minify raw rules as list of strings
-fn minify_rules(rules: &[RawRule], classes: &Classes) -> Result<Vec<String>, Error> {
+fn minify_rules(
+    rules: &[RawRule],
+    classes: &Classes,
+    macros: &Macros,
+) -> Result<Vec<String>, Error> {
     let mut strings = Vec::new();
 
     for RawRule {
         intent,
         pattern,
-        line,
+        location,
         ..
     } in rules
     {
         strings.push(format!(
             "{}{}",
             if *intent { '+' } else { '!' },
-            replace_classes(pattern, classes, *line)?
+            replace_classes(pattern, classes, macros, location)?
         ));
     }
 
@@ -12,13 +12,13 @@ fn minify_works() {
             pattern: "^⟨_⟩+$".to_string(),
             intent: true,
             note: Some(Note("Should contain ⟨a⟩".to_string())),
-            line: 0,
+            location: Default::default(),
         },
         RawRule {
             pattern: "⟨V⟩⟨V⟩".to_string(),
             intent: false,
             note: None,
-            line: 0,
+            location: Default::default(),
         },
     ];
 
@@ -29,6 +29,9 @@ fn minify_works() {
         Test(TestDraft {
             word: "ax".to_string(),
             intent: false,
+            reason: None,
+            transcription: None,
+            underlying: None,
         }),
         //
         Info(Note("this is a note".to_string())),
@@ -36,6 +39,9 @@ fn minify_works() {
         Test(TestDraft {
             word: "abc".to_string(),
             intent: true,
+            reason: None,
+            transcription: None,
+            underlying: None,
         }),
         //
         Info(Note("another note".to_string())),
@@ -43,10 +49,22 @@ fn minify_works() {
         Test(TestDraft {
             word: "hello".to_string(),
             intent: false,
+            reason: None,
+            transcription: None,
+            underlying: None,
         }),
     ];
 
-    let minified = minify(mode, &classes, &raw_rules, &messages, true).unwrap();
+    let minified = minify(
+        mode,
+        None,
+        &classes,
+        &Macros::new(),
+        &raw_rules,
+        &messages,
+        true,
+    )
+    .unwrap();
 
     assert_eq!(
         minified,
@@ -55,7 +73,16 @@ fn minify_works() {
 
     // * ...with tests disabled
 
-    let minified = minify(mode, &classes, &raw_rules, &messages, false).unwrap();
+    let minified = minify(
+        mode,
+        None,
+        &classes,
+        &Macros::new(),
+        &raw_rules,
+        &messages,
+        false,
+    )
+    .unwrap();
 
     assert_eq!(minified, "~//;+^[[ptk][aeiou]]+$;![aeiou][aeiou]");
 
@@ -68,12 +95,24 @@ fn minify_works() {
         Test(TestDraft {
             word: "abc".to_string(),
             intent: true,
+            reason: None,
+            transcription: None,
+            underlying: None,
         }),
         //
         Info(Note("another note".to_string())),
     ];
 
-    let minified = minify(mode, &classes, &raw_rules, &messages, true).unwrap();
+    let minified = minify(
+        mode,
+        None,
+        &classes,
+        &Macros::new(),
+        &raw_rules,
+        &messages,
+        true,
+    )
+    .unwrap();
 
     assert_eq!(minified, "~//;+^[[ptk][aeiou]]+$;![aeiou][aeiou];?+abc");
 
@@ -84,15 +123,30 @@ fn minify_works() {
         Test(TestDraft {
             word: "ax".to_string(),
             intent: false,
+            reason: None,
+            transcription: None,
+            underlying: None,
         }),
         //
         Test(TestDraft {
             word: "hello".to_string(),
             intent: false,
+            reason: None,
+            transcription: None,
+            underlying: None,
         }),
     ];
 
-    let minified = minify(mode, &classes, &raw_rules, &messages, true).unwrap();
+    let minified = minify(
+        mode,
+        None,
+        &classes,
+        &Macros::new(),
+        &raw_rules,
+        &messages,
+        true,
+    )
+    .unwrap();
 
     assert_eq!(
         minified,
@@ -101,7 +155,16 @@ fn minify_works() {
 
     // * ...with tests disabled
 
-    let minified = minify(mode, &classes, &raw_rules, &messages, false).unwrap();
+    let minified = minify(
+        mode,
+        None,
+        &classes,
+        &Macros::new(),
+        &raw_rules,
+        &messages,
+        false,
+    )
+    .unwrap();
 
     assert_eq!(minified, "~//;+^[[ptk][aeiou]]+$;![aeiou][aeiou]");
 
@@ -112,15 +175,36 @@ fn minify_works() {
         Info(Note("another note".to_string())),
     ];
 
-    let minified = minify(mode, &classes, &[], &messages, true).unwrap();
+    let minified = minify(mode, None, &classes, &Macros::new(), &[], &messages, true).unwrap();
 
     assert_eq!(minified, "~//;");
 
     // * ...with tests disabled
 
-    let minified = minify(mode, &classes, &raw_rules, &messages, false).unwrap();
+    let minified = minify(
+        mode,
+        None,
+        &classes,
+        &Macros::new(),
+        &raw_rules,
+        &messages,
+        false,
+    )
+    .unwrap();
 
     assert_eq!(minified, "~//;+^[[ptk][aeiou]]+$;![aeiou][aeiou]");
 }
 
+#[test]
+fn minify_keeps_backtrack_limit() {
+    let draft = crate::Draft::from("@backtrack-limit 5\n+ ^(a+)+$\n?+ aaa").unwrap();
+
+    let minified = draft.minify(true).unwrap();
+    assert_eq!(minified, "~<>;@backtrack-limit 5;+^(a+)+$;?+aaa");
+
+    let parsed = crate::Draft::from(&minified).unwrap();
+    assert_eq!(parsed.backtrack_limit, Some(5));
+    assert_eq!(parsed.minify(true).unwrap(), minified);
+}
+
 //TODO Failed to parse
@@ -2,30 +2,59 @@
 ///
 /// Only used in the `parse` module
 macro_rules! parse_error {
-    ( $line: expr, $kind: ident ) => {
+    ( $location: expr, $kind: ident ) => {
         Err($crate::error::Error::Parse(
             $crate::error::ParseError::$kind,
-            $line,
+            $location.clone(),
         ))
     };
-    ( $line: expr, $kind: ident, $( $value: expr )* ) => {
+    ( $location: expr, $kind: ident, $( $value: expr )* ) => {
         Err($crate::error::Error::Parse(
             $crate::error::ParseError::$kind(
-                $( $value )*
+                $( $value ),*
             ),
-            $line,
+            $location.clone(),
         ))
     };
 }
 
+/// Allophonic rewrite rules, to derive narrow transcriptions
+mod allophony;
+/// Build `Draft` with methods
+mod builder;
+/// Sound changes, to evolve words into a daughter language
+mod changes;
+/// Enumerate segments of classes
+mod enumerate;
+/// Features of segments, and feature bundles
+mod features;
+/// Resolve included files
+mod include;
+/// Classes with parameters
+mod macros;
 /// Minify draft to string
 mod minify;
+/// Mappings between orthography and transcription
+mod orthography;
 /// Parse functions
 mod parse;
+/// Built-in preludes of classes
+mod prelude;
 /// Substitute class names recursively
 mod replace;
+/// Segment lists of classes, with segments of multiple characters
+mod segments;
+/// Set operations between classes
+mod sets;
+/// Convert draft to readable source
+mod source;
+/// Serialize and deserialize `Draft` and `Rule`
+#[cfg(feature = "serde")]
+mod serialize;
 /// Split file into statements
 mod statements;
+/// Delimiters and alphabets of transcription modes, for tests
+mod transcription;
 
 use std::collections::HashMap;
 
@@ -34,19 +63,44 @@ use fancy_regex::Regex;
 // Holds types for `Draft` struct
 // mod types;
 
-pub(crate) use self::replace::replace_classes;
 // pub use self::types::*;
 
 // use std::collections::HashMap;
 
 // use fancy_regex_macro::regex;
 
-use self::minify::minify;
+pub use self::{builder::DraftBuilder, enumerate::MAX_SEGMENTS};
+pub(crate) use self::{
+    prelude::PRELUDES, replace::replace_angle_brackets, statements::is_class_intersection,
+};
+
+use self::{
+    allophony::{Allophone, RawAllophone},
+    changes::{Change, RawChange},
+    features::FeatureRow,
+    macros::Macros,
+    minify::minify,
+    orthography::Mapping,
+};
 // parse::parse_rules, statements::split_statements
-use crate::{error::Error, outcome::Outcome};
+use crate::{
+    error::{Error, Location},
+    outcome::{EvalMode, Outcome},
+};
+
+/// Names of valid directives (statements beginning with `@`)
+pub(crate) const DIRECTIVES: &[&str] = &[
+    "include",
+    "backtrack-limit",
+    "features",
+    "prelude",
+    "map",
+    "allophone",
+    "change",
+];
 
-/// Maps class names of pattern and line number, for raw classes
-pub(crate) type Classes = HashMap<String, (String, usize)>;
+/// Maps class names of pattern and location, for raw or resolved classes
+pub(crate) type Classes = HashMap<String, (String, Location)>;
 
 /// Parsed *Phonet* file
 ///
@@ -81,10 +135,10 @@ pub(crate) type Classes = HashMap<String, (String, usize)>;
 /// # use phonet::Draft;
 /// use phonet::error::{Error, ParseError::*};
 ///
-/// let draft = Draft::from("@");
+/// let draft = Draft::from("%");
 ///
 /// assert!(matches!(draft, Err(
-///     Error::Parse(UnknownStatementOperator('@'), _)
+///     Error::Parse(UnknownStatementOperator('%'), _)
 /// )));
 /// ```
 #[derive(Debug, PartialEq)]
@@ -101,22 +155,53 @@ pub struct Draft {
     pub mode: Mode,
     /// Amount of tests in `messages` field
     pub test_count: usize,
+    /// Maximum amount of backtracking for a rule to run on a single word (optional)
+    ///
+    /// Defined with `@backtrack-limit`. Uses the default limit of `fancy_regex` if `None`
+    pub backtrack_limit: Option<usize>,
 
     pub(crate) raw_rules: Vec<RawRule>,
     pub(crate) raw_classes: Classes,
+    /// Classes with parameters, which are expanded where they are used
+    pub(crate) macros: Macros,
+    /// Names of built-in preludes, imported with `@prelude`
+    ///
+    /// Classes of preludes are included in `classes`, but not `raw_classes`
+    pub(crate) preludes: Vec<String>,
+    /// Features of segments, defined with `@features`
+    pub(crate) features: Vec<FeatureRow>,
+    /// Mappings of graphemes to phonemes, defined with `@map`
+    pub(crate) orthography: Vec<Mapping>,
+    /// Allophonic rewrite rules, defined with `@allophone`
+    pub(crate) raw_allophones: Vec<RawAllophone>,
+    /// Allophonic rewrite rules, with classes substituted, from `raw_allophones`
+    pub(crate) allophones: Vec<Allophone>,
+    /// Sound changes, defined with `@change`
+    pub(crate) raw_changes: Vec<RawChange>,
+    /// Sound changes, with classes substituted, from `raw_changes`
+    pub(crate) changes: Vec<Change>,
+    /// Classes with values substituted, from `raw_classes`
+    pub(crate) classes: Classes,
 }
 
 /// Pattern rule for `Draft`
+///
+/// With the `serde` feature, the pattern is serialized as the source pattern and the expanded pattern,
+/// and is recompiled from the expanded pattern when deserialized
 #[derive(Debug, Clone)]
 pub struct Rule {
-    /// Whether pattern should match or not, for a test to be valid
+    /// Regex pattern, with classes substituted
     pub pattern: Regex,
-    /// Regex pattern
+    /// Regex pattern as written in file, before classes are substituted
+    pub source: String,
+    /// Whether pattern should match or not, for a test to be valid
     pub intent: bool,
     /// Note for rule (optional)
     ///
     /// Reason given, if test fails from this rule
     pub note: Option<Note>,
+    /// Location of rule in file
+    pub location: Location,
 }
 
 /// Mirrors `Rule` struct, but with `String` instead of `Regex`
@@ -130,8 +215,8 @@ pub(crate) struct RawRule {
     ///
     /// Reason given, if test fails from this rule
     pub note: Option<Note>,
-    /// Line number
-    pub line: usize,
+    /// Location of statement
+    pub location: Location,
 }
 
 /// Single message to be displayed in `Draft` and `Outcome`
@@ -139,7 +224,8 @@ pub(crate) struct RawRule {
 /// May be a `Info` (`Note`) and `Test`
 ///
 /// `Test` type should hold `TestDraft` or `TestOutcome`, for `Draft` and `Outcome` structs respectively
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Message<T> {
     /// Plain text `Note`
     Info(Note),
@@ -151,25 +237,53 @@ pub enum Message<T> {
 ///
 ///TODO Remove this - use string
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Note(pub String);
 
 /// Test that has not yet ran, for `Draft`
+///
+/// Use `TestDraft::new` to create. More fields may be added in minor versions
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct TestDraft {
     /// String to test
     pub word: String,
     /// Whether test should be valid or not to pass
     pub intent: bool,
+    /// Expected reason for test to be invalid (optional)
+    ///
+    /// If given, test only passes if the note of the first violated rule matches this
+    pub reason: Option<Note>,
+    /// Expected broad transcription of word (optional), such as `/kato/` in `?+ kato /kato/`
+    ///
+    /// If given, test only passes if the word is transcribed to this, with the mappings of the draft
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transcription: Option<String>,
+    /// Broad transcription which `word` was derived from with allophones (optional), such as `/kato/` in `?+ /kato/`
+    ///
+    /// Only given in narrow transcription, where `word` is the derived narrow transcription
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub underlying: Option<String>,
 }
 
 /// Transcription mode of file
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mode {
     /// Use `~<>`
+    ///
+    /// Tests may be written as `<kato>`
+    #[default]
     Romanized,
     /// Use `~//`
+    ///
+    /// Tests may be written as `/kato/`
     Broad,
     /// Use `~[]`
+    ///
+    /// Tests may be written as `[kʰato]`, and must only contain IPA characters.
+    /// Tests written as `/kato/` are derived to narrow transcription with allophones
     Narrow,
 }
 
@@ -179,13 +293,23 @@ impl Draft {
         Outcome::run(self)
     }
 
+    /// Run drafted tests, with an evaluation mode
+    ///
+    /// Use `EvalMode::AllViolations` to report every rule which a test violates
+    pub fn run_with(&self, eval_mode: EvalMode) -> Outcome {
+        Outcome::run_with(self, eval_mode)
+    }
+
     /// Returns a minified version of the original file of the `Draft`
     ///
-    /// If `with_tests` is true, the minified string will include tests
+    /// If `with_tests` is true, the minified string will include tests.
+    /// Notes and mappings are not included, so tests do not keep their expected reasons or transcriptions
     pub fn minify(&self, with_tests: bool) -> Result<String, Error> {
         minify(
             self.mode,
-            &self.raw_classes,
+            self.backtrack_limit,
+            &self.classes,
+            &self.macros,
             &self.raw_rules,
             &self.messages,
             with_tests,
@@ -194,6 +318,7 @@ impl Draft {
 }
 
 // Scuffed equality check for `Rule`
+// Source and location are not compared
 impl PartialEq for Rule {
     fn eq(&self, other: &Self) -> bool {
         self.intent == other.intent
@@ -215,9 +340,26 @@ impl<T> Message<T> {
     }
 }
 
-impl Default for Mode {
-    fn default() -> Self {
-        Self::Romanized
+impl TestDraft {
+    /// Create test of a word, without an expected reason or transcription
+    ///
+    /// Other fields may be set afterwards, such as `reason`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::draft::{Note, TestDraft};
+    /// let mut test = TestDraft::new(false, "taaso");
+    /// test.reason = Some(Note("Vowel clusters".to_string()));
+    /// ```
+    pub fn new(intent: bool, word: &str) -> Self {
+        Self {
+            word: word.to_string(),
+            intent,
+            reason: None,
+            transcription: None,
+            underlying: None,
+        }
     }
 }
 
@@ -247,6 +389,17 @@ impl Mode {
         }
     }
 
+    /// Get opening and closing delimiters of `Mode`, such as `/` and `/` for broad transcription
+    pub fn delimiters(self) -> (char, char) {
+        use Mode::*;
+
+        match self {
+            Romanized => ('<', '>'),
+            Broad => ('/', '/'),
+            Narrow => ('[', ']'),
+        }
+    }
+
     /// Convert `Mode` to basic characters
     pub fn as_str(self) -> &'static str {
         use Mode::*;
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+
+use super::Draft;
+use crate::error::{Location, ParseError};
+
+/// Grapheme of the orthography, and the phoneme it is written for, with a single `@map` directive
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Mapping {
+    /// Romanized letters, such as `ch`
+    pub grapheme: String,
+    /// Broad transcription, such as `t͡ʃ`
+    pub phoneme: String,
+    /// Location of statement
+    pub location: Location,
+}
+
+impl fmt::Display for Mapping {
+    /// Convert mapping to `@map` directive
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@map {} /{}/", self.grapheme, self.phoneme)
+    }
+}
+
+impl Draft {
+    /// Convert a romanized word to broad transcription, using the mappings of the draft
+    ///
+    /// At each position, the longest grapheme is replaced with its phoneme.
+    /// Letters without a mapping are kept as they are
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::Draft;
+    /// let draft = Draft::from("@map ch /t͡ʃ/; @map c /k/; @map y /j/").unwrap();
+    ///
+    /// assert_eq!(draft.transcribe("chaya"), "t͡ʃaja");
+    /// assert_eq!(draft.romanize("kat͡ʃa"), "cacha");
+    /// ```
+    pub fn transcribe(&self, word: &str) -> String {
+        convert(word, &self.orthography, true)
+    }
+
+    /// Convert a word in broad transcription to romanized letters, using the mappings of the draft
+    ///
+    /// At each position, the longest phoneme is replaced with its grapheme.
+    /// If a phoneme is written with multiple graphemes, the first mapping is used
+    pub fn romanize(&self, word: &str) -> String {
+        convert(word, &self.orthography, false)
+    }
+}
+
+/// Parse argument of `@map` directive, such as `ch /t͡ʃ/`, into grapheme and phoneme
+///
+/// Grapheme may be written in angle brackets, and phoneme may be written in slashes
+pub(super) fn parse_mapping(argument: &str) -> Result<(String, String), ParseError> {
+    let words: Vec<_> = argument.split_whitespace().collect();
+    let [grapheme, phoneme] = words.as_slice() else {
+        return Err(ParseError::InvalidMapping(argument.trim().to_string()));
+    };
+
+    let grapheme = strip_brackets(grapheme, '<', '>');
+    let phoneme = strip_brackets(phoneme, '/', '/');
+
+    if grapheme.is_empty() || phoneme.is_empty() {
+        return Err(ParseError::InvalidMapping(argument.trim().to_string()));
+    }
+    Ok((grapheme.to_string(), phoneme.to_string()))
+}
+
+/// Remove opening and closing brackets of word, if both are given
+pub(super) fn strip_brackets(word: &str, open: char, close: char) -> &str {
+    word.strip_prefix(open)
+        .and_then(|inner| inner.strip_suffix(close))
+        .unwrap_or(word)
+}
+
+/// Convert word with mappings, replacing the longest mapped segment at each position
+///
+/// Graphemes are replaced with phonemes if `to_phonemes` is true, otherwise phonemes are replaced with graphemes.
+/// Characters which are not mapped are kept as they are
+fn convert(word: &str, mappings: &[Mapping], to_phonemes: bool) -> String {
+    let mut output = String::new();
+    let mut rest = word;
+
+    while let Some(ch) = rest.chars().next() {
+        // Longest match, or first mapping if the same length
+        let mut longest: Option<(&str, &str)> = None;
+        for Mapping {
+            grapheme, phoneme, ..
+        } in mappings
+        {
+            let (from, to) = if to_phonemes {
+                (grapheme, phoneme)
+            } else {
+                (phoneme, grapheme)
+            };
+            if rest.starts_with(from.as_str())
+                && longest.map_or(true, |(longest, _)| from.len() > longest.len())
+            {
+                longest = Some((from, to));
+            }
+        }
+
+        match longest {
+            Some((from, to)) => {
+                output.push_str(to);
+                rest = &rest[from.len()..];
+            }
+            None => {
+                output.push(ch);
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+
+    output
+}
@@ -0,0 +1,150 @@
+use super::*;
+use crate::{
+    draft::{Message, Mode, TestDraft},
+    error::Error,
+    outcome::{FailKind, PassStatus},
+    DraftBuilder,
+};
+
+/// Mappings of a romanized orthography
+const ORTHOGRAPHY: &str = "
+    @map ch /t͡ʃ/
+    @map c /k/
+    @map k /k/
+    @map <y> /j/
+    @map ng /ŋ/
+";
+
+#[test]
+fn parse_mapping_works() {
+    assert_eq!(
+        parse_mapping("ch /t͡ʃ/").unwrap(),
+        ("ch".to_string(), "t͡ʃ".to_string())
+    );
+    assert_eq!(
+        parse_mapping("<y>  j").unwrap(),
+        ("y".to_string(), "j".to_string())
+    );
+
+    assert!(matches!(
+        parse_mapping("ch"),
+        Err(ParseError::InvalidMapping(argument)) if argument == "ch"
+    ));
+    assert!(matches!(
+        parse_mapping("ch /t͡ʃ/ c"),
+        Err(ParseError::InvalidMapping(_))
+    ));
+    assert!(matches!(
+        parse_mapping("<> //"),
+        Err(ParseError::InvalidMapping(_))
+    ));
+}
+
+#[test]
+fn convert_works() {
+    let draft = Draft::from(ORTHOGRAPHY).unwrap();
+
+    assert_eq!(draft.transcribe("chaya"), "t͡ʃaja");
+    assert_eq!(draft.transcribe("ngaca"), "ŋaka");
+    // Letters without a mapping are kept
+    assert_eq!(draft.transcribe("sachi"), "sat͡ʃi");
+
+    // First mapping is used for phonemes with multiple graphemes
+    assert_eq!(draft.romanize("kat͡ʃa"), "cacha");
+    assert_eq!(draft.romanize("ŋjo"), "ngyo");
+
+    // Longest grapheme is replaced first, regardless of order
+    let draft = Draft::from("@map n /n/; @map g /ɡ/; @map ng /ŋ/").unwrap();
+    assert_eq!(draft.transcribe("nga"), "ŋa");
+    assert_eq!(draft.romanize("nɡa"), "nga");
+}
+
+#[test]
+fn transcription_tests_work() {
+    let draft = Draft::from(&format!(
+        "{}
+        + ^ [a-z]+ $
+        ?+ chaya /t͡ʃaja/ kaca /kaka/ ngo
+        ?! chA /t͡ʃA/",
+        ORTHOGRAPHY
+    ))
+    .unwrap();
+
+    let transcriptions: Vec<_> = draft
+        .messages
+        .iter()
+        .filter_map(|msg| match msg {
+            Message::Test(test) => Some(test.transcription.as_deref()),
+            Message::Info(_) => None,
+        })
+        .collect();
+    assert_eq!(
+        transcriptions,
+        [Some("t͡ʃaja"), Some("kaka"), None, Some("t͡ʃA")]
+    );
+    assert_eq!(draft.run().fail_count, 0);
+
+    let draft = DraftBuilder::new()
+        .map("ch", "t͡ʃ")
+        .test_transcription(true, "cha", "/ʃa/")
+        .test_transcription(true, "chi", "t͡ʃi")
+        .build()
+        .unwrap();
+    let outcome = draft.run();
+    assert_eq!(outcome.fail_count, 1);
+    assert!(matches!(
+        &outcome.messages[0],
+        Message::Test(test) if test.status == PassStatus::Fail(FailKind::WrongTranscription(
+            "ʃa".to_string(),
+            "t͡ʃa".to_string()
+        ))
+    ));
+}
+
+#[test]
+fn transcriptions_in_other_modes_return_error() {
+    assert!(matches!(
+        DraftBuilder::new()
+            .mode(Mode::Broad)
+            .test_transcription(true, "kato", "kato")
+            .build(),
+        Err(Error::Parse(ParseError::TranscriptionNotRomanized(word), _)) if word == "kato"
+    ));
+
+    // Words in slashes are only transcriptions in romanized mode
+    let draft = Draft::from("~//; ?+ kato /pita/").unwrap();
+    assert_eq!(draft.test_count, 2);
+    assert_eq!(draft.mode, Mode::Broad);
+    assert!(draft.messages.iter().all(|msg| matches!(
+        msg,
+        Message::Test(TestDraft {
+            transcription: None,
+            ..
+        })
+    )));
+
+    // Words in slashes are only transcriptions after a word without slashes
+    let draft = Draft::from("~//; ?+ /kato/ /sato/").unwrap();
+    assert_eq!(draft.test_count, 2);
+
+    assert!(matches!(
+        Draft::from("@map ch"),
+        Err(Error::Parse(ParseError::InvalidMapping(_), _))
+    ));
+}
+
+#[test]
+fn mappings_are_converted_to_source() {
+    let draft = Draft::from("@map <ch> t͡ʃ; ?+ cha /t͡ʃa/ ca").unwrap();
+
+    assert_eq!(
+        draft.to_source(),
+        "~<>\n\n@map ch /t͡ʃ/\n\n?+ cha /t͡ʃa/ ca\n"
+    );
+
+    // Source parses to the same mappings and tests
+    let source = draft.to_source();
+    let parsed = Draft::from(&source).unwrap();
+    assert_eq!(parsed.messages, draft.messages);
+    assert_eq!(parsed.to_source(), source);
+}
@@ -1,251 +1,750 @@
 #[cfg(test)]
 mod tests;
 
-use std::{collections::HashMap, str::Chars};
+use std::{fs, path::Path, str::Chars};
 
-use fancy_regex::Regex;
+use fancy_regex::{Regex, RegexBuilder};
 use fancy_regex_macro::regex;
 
 use super::{
-    replace::replace_classes, statements::split_statements, Classes, Draft, Message::*, Mode, Note,
-    RawRule, Rule, TestDraft,
+    allophony::{check_allophone, RawAllophone},
+    changes::{check_change, RawChange},
+    features::{parse_features, FeatureRow},
+    include::{load_statements, split_directive},
+    macros::{split_parameters, ClassMacro, Macros},
+    orthography::{parse_mapping, Mapping},
+    prelude::prelude,
+    replace::replace_classes,
+    statements::span_of,
+    Classes, Draft, DraftBuilder,
+    Message::{self, *},
+    Mode, Note, RawRule, Rule, TestDraft,
+};
+use crate::{
+    error::{Error, Location},
+    REGEX_MATCH_FAIL,
 };
-use crate::{error::Error, REGEX_MATCH_FAIL};
 
 impl Draft {
     /// Parse Phonet `Draft` from file
+    ///
+    /// Files cannot be included with `@include` - Use `Draft::from_path` instead
     pub fn from(file: &str) -> Result<Draft, Error> {
-        // Split file into statements
-        let statements = split_statements(file);
+        Self::parse(file, None, &[])
+    }
 
-        // Field builders
-        let mut messages = Vec::new();
-        let mut mode_and_name: Option<(Mode, Option<String>)> = None;
+    /// Parse Phonet `Draft` from file, with selected tags for conditional statements
+    ///
+    /// Statements with a condition (such as `[strict]`) are only included if the condition is met.
+    /// Statements without a condition are always included
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::Draft;
+    /// let file = "[strict] ! ^k; ?+ kato";
+    ///
+    /// assert_eq!(Draft::from(file).unwrap().run().fail_count, 0);
+    /// assert_eq!(Draft::from_with_tags(file, &["strict"]).unwrap().run().fail_count, 1);
+    /// ```
+    pub fn from_with_tags(file: &str, tags: &[&str]) -> Result<Draft, Error> {
+        Self::parse(file, None, tags)
+    }
 
-        // Field builders without regex parsed
-        let mut raw_rules = Vec::new();
-        let mut raw_classes: Classes = HashMap::new();
+    /// Read and parse Phonet `Draft` from file path
+    ///
+    /// Paths of included files (with `@include`) are relative to the directory of the file which includes them
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use phonet::Draft;
+    /// let draft = Draft::from_path("dialect.phonet").unwrap();
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Draft, Error> {
+        Self::from_path_with_tags(path, &[])
+    }
 
-        // Most recent note
-        let mut last_note: Option<Note> = None;
+    /// Read and parse Phonet `Draft` from file path, with selected tags for conditional statements
+    ///
+    /// See `Draft::from_with_tags`
+    pub fn from_path_with_tags(path: impl AsRef<Path>, tags: &[&str]) -> Result<Draft, Error> {
+        let path = path.as_ref();
 
-        // Loop statements
-        for (statement, line) in statements {
-            let statement = statement.trim();
+        let file = match fs::read_to_string(path) {
+            Ok(file) => file,
+            Err(err) => return Err(Error::ReadFile(path.to_path_buf(), err)),
+        };
+
+        Self::parse(&file, Some(path), tags)
+    }
 
-            // Skip if blank
-            if statement.is_empty() {
-                continue;
+    /// Parse Phonet `Draft` from file, with optional path of file, and selected tags
+    ///
+    /// Parsing continues after an invalid statement, so every error in the file is returned
+    fn parse(file: &str, path: Option<&Path>, tags: &[&str]) -> Result<Draft, Error> {
+        // Errors of every statement
+        let mut errors = Vec::new();
+
+        // Files currently being included, to detect cyclic includes
+        let mut stack = Vec::new();
+        if let Some(path) = path {
+            stack.push(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+        }
+        // Files already included
+        let mut loaded = stack.clone();
+
+        // Split file into statements, including other files
+        let statements = load_statements(file, path, &mut stack, &mut loaded, &mut errors);
+
+        // Loop statements
+        let mut parser = Parser {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+        for (statement, location) in statements {
+            // Save error and continue to next statement
+            if let Err(err) = parser.parse_statement(&statement, &location) {
+                errors.push(err);
             }
+        }
 
-            // Get line operator first character
-            let mut chars = statement.chars();
-            let Some(operator) = chars.next() else {
-                continue;
-            };
+        let Parser {
+            messages,
+            mode_and_name,
+            raw_rules,
+            raw_classes,
+            macros,
+            preludes,
+            features,
+            orthography,
+            raw_allophones,
+            raw_changes,
+            backtrack_limit,
+            test_locations,
+            last_note,
+            ..
+        } = parser;
 
-            // Match line operator
-            match operator {
-                // Comment - this should have been removed in `split_statements` function
-                '#' => unrecoverable_error!(
-                    "A comment statement slipped past the initial statement parser!"
-                ),
+        // Use default mode and None name if not specified
+        let (mode, name) = mode_and_name.unwrap_or_default();
+
+        // Compile classes and rules, the same as a `DraftBuilder`
+        DraftBuilder {
+            messages,
+            name,
+            mode,
+            backtrack_limit,
+            raw_rules,
+            raw_classes,
+            macros,
+            preludes,
+            features,
+            orthography,
+            raw_allophones,
+            raw_changes,
+            test_locations,
+            last_note,
+            errors,
+            ..Default::default()
+        }
+        .build()
+    }
+}
+
+/// Field builders of `Draft`, while statements are being parsed
+#[derive(Default)]
+struct Parser {
+    messages: Vec<Message<TestDraft>>,
+    mode_and_name: Option<(Mode, Option<String>)>,
+    backtrack_limit: Option<usize>,
+
+    // Field builders without regex parsed
+    raw_rules: Vec<RawRule>,
+    raw_classes: Classes,
+    macros: Macros,
+    preludes: Vec<String>,
+    features: Vec<FeatureRow>,
+    orthography: Vec<Mapping>,
+    raw_allophones: Vec<RawAllophone>,
+    raw_changes: Vec<RawChange>,
+
+    /// Location of each test, in order, for errors of test words
+    test_locations: Vec<Location>,
+    /// Most recent note
+    last_note: Option<Note>,
+    /// Selected tags, for conditional statements
+    tags: Vec<String>,
+}
 
-                // Mode
-                '~' => {
-                    // Fail if mode is already defined
-                    if mode_and_name.is_some() {
-                        return parse_error!(line, ModeAlreadyDefined);
+impl Parser {
+    /// Parse a single statement, and add it to the fields
+    ///
+    /// Spans of errors are relative to the untrimmed `statement`
+    fn parse_statement(&mut self, statement: &str, location: &Location) -> Result<(), Error> {
+        let full = statement;
+        let statement = statement.trim();
+
+        // Skip if blank
+        if statement.is_empty() {
+            return Ok(());
+        }
+
+        // Get condition, in brackets before operator (optional)
+        let statement = match statement.strip_prefix('[') {
+            Some(rest) => {
+                let Some((condition, rest)) = rest.split_once(']') else {
+                    return parse_error!(
+                        location.with_span(span_of(full, statement)),
+                        UnclosedCondition
+                    );
+                };
+                let rest = rest.trim_start();
+
+                // Only classes, rules, tests, and notes can be conditional
+                if !matches!(rest.chars().next(), Some('$' | '+' | '!' | '?' | '*')) {
+                    let brackets = &statement[..condition.len() + 2];
+                    return parse_error!(
+                        location.with_span(span_of(full, brackets)),
+                        ConditionNotAllowed
+                    );
+                }
+
+                // Skip statement if condition is not met
+                if !self.is_condition_met(condition, full, location)? {
+                    // Following rules do not belong to previous note
+                    if rest.starts_with('*') {
+                        self.last_note = None;
                     }
+                    return Ok(());
+                }
+                rest
+            }
+            None => statement,
+        };
+
+        // Get line operator first character
+        let mut chars = statement.chars();
+        let Some(operator) = chars.next() else {
+            return Ok(());
+        };
+
+        // Match line operator
+        match operator {
+            // Comment - this should have been removed in `split_statements` function
+            '#' => {
+                unrecoverable_error!(
+                    "A comment statement slipped past the initial statement parser!"
+                )
+            }
+
+            // Mode
+            '~' => {
+                // Fail if mode is already defined
+                if self.mode_and_name.is_some() {
+                    return parse_error!(
+                        location.with_span(span_of(full, statement)),
+                        ModeAlreadyDefined
+                    );
+                }
 
-                    // Remove spaces
-                    while chars.as_str().starts_with(' ') {
-                        chars.next();
+                // Remove spaces
+                while chars.as_str().starts_with(' ') {
+                    chars.next();
+                }
+                let specifier = chars.as_str();
+
+                // Separate mode specifiers and name
+                let (first, name, last) = chars_first_middle_last(&mut chars);
+
+                // Use `None` if name is empty
+                let name = if name.trim().is_empty() {
+                    None
+                } else {
+                    Some(name.trim().to_string())
+                };
+
+                // Select mode
+                self.mode_and_name = Some(match Mode::from_options(first, last) {
+                    Some(value) => (value, name),
+                    None => {
+                        return parse_error!(
+                            location.with_span(span_of(full, specifier)),
+                            InvalidModeSpecifier
+                        )
                     }
+                });
+            }
 
-                    // Separate mode specifiers and name
-                    let (first, name, last) = chars_first_middle_last(&mut chars);
-
-                    // Use `None` if name is empty
-                    let name = if name.trim().is_empty() {
-                        None
-                    } else {
-                        Some(name.trim().to_string())
-                    };
-
-                    // Select mode
-                    mode_and_name = Some(match Mode::from_options(first, last) {
-                        Some(value) => (value, name),
-                        None => return parse_error!(line, InvalidModeSpecifier),
-                    });
+            // Class
+            '$' => {
+                let mut split = chars.as_str().splitn(2, '=');
+
+                // Get class name
+                let Some(name) = split.next() else {
+                    return parse_error!(location.with_span(span_of(full, statement)), NoClassName);
+                };
+                let name = name.trim();
+                let name_location = location.with_span(span_of(full, name));
+
+                // Get parameters of class macro, such as `$Geminate(x) = <x><x>` (optional)
+                let (name, parameters) = match split_parameters(name) {
+                    Ok(split) => split,
+                    Err(kind) => return Err(Error::Parse(kind, name_location)),
+                };
+
+                // Check that name is valid, and does not exist
+                check_class_name(name, &self.raw_classes, &self.macros, &name_location)?;
+
+                // Get class pattern
+                let Some(pattern) = split.next() else {
+                    return parse_error!(name_location, NoClassPattern, name.to_string());
+                };
+                let pattern = pattern.trim();
+                let location = location.with_span(span_of(full, pattern));
+
+                // Add class or class macro
+                // Spaces are removed and classes are substituted when classes are resolved
+                match parameters {
+                    Some(parameters) => {
+                        self.macros.insert(
+                            name.to_string(),
+                            ClassMacro {
+                                parameters,
+                                pattern: pattern.to_string(),
+                                location,
+                            },
+                        );
+                    }
+                    None => {
+                        self.raw_classes
+                            .insert(name.to_string(), (pattern.to_string(), location));
+                    }
                 }
+            }
+
+            // Rule
+            '+' | '!' => {
+                // `+` for true, `!` for false
+                let intent = operator == '+';
 
-                // Class
-                '$' => {
-                    let mut split = chars.as_str().split('=');
+                // Spaces are removed when classes are replaced
+                let pattern = chars.as_str().trim();
 
-                    // Get class name
-                    let Some(name) = split.next() else {
-                        return parse_error!(line, NoClassName);
-                    };
-                    let name = name.trim();
+                // Get most recent note, owned
+                let note = self.last_note.clone();
 
-                    // Check if name is valid
-                    if !regex!(r"^\w+$").is_match(name).expect(REGEX_MATCH_FAIL) {
-                        return parse_error!(line, InvalidClassName, name.to_string());
+                // Add rule
+                self.raw_rules.push(RawRule {
+                    intent,
+                    pattern: pattern.to_string(),
+                    note,
+                    location: location.with_span(span_of(full, pattern)),
+                })
+            }
+
+            // Test
+            '?' => {
+                // Remove spaces
+                while chars.as_str().starts_with(' ') {
+                    chars.next();
+                }
+                let intent_str = chars.as_str();
+
+                // Get intent
+                let intent = match chars.next() {
+                    // Should be INVALID to pass
+                    Some('+') => true,
+                    // Should be VALID to pass
+                    Some('!') => false,
+
+                    // Unknown or no character
+                    _ => {
+                        let intent_str = match intent_str.chars().next() {
+                            Some(ch) => &intent_str[..ch.len_utf8()],
+                            None => intent_str,
+                        };
+                        return parse_error!(
+                            location.with_span(span_of(full, intent_str)),
+                            InvalidTestIntent
+                        );
                     }
+                };
+
+                // Get expected reason, in brackets directly after intent (optional)
+                let reason = match chars.as_str().strip_prefix('(') {
+                    Some(rest) => {
+                        let Some((reason, words)) = rest.split_once(')') else {
+                            return parse_error!(
+                                location.with_span(span_of(full, chars.as_str().trim_end())),
+                                UnclosedTestReason
+                            );
+                        };
+
+                        // Reason cannot be empty
+                        let reason = reason.trim();
+                        if reason.is_empty() {
+                            let brackets = &chars.as_str()[..chars.as_str().len() - words.len()];
+                            return parse_error!(
+                                location.with_span(span_of(full, brackets)),
+                                EmptyTestReason
+                            );
+                        }
+
+                        // Only invalid tests can have a reason
+                        if intent {
+                            return parse_error!(
+                                location.with_span(span_of(full, reason)),
+                                ReasonForValidTest
+                            );
+                        }
 
-                    // Check that class name does not exist
-                    if raw_classes.get(name).is_some() {
-                        return parse_error!(line, ClassAlreadyExists, name.to_string());
+                        chars = words.chars();
+                        Some(Note(reason.to_string()))
+                    }
+                    None => None,
+                };
+
+                // Index of previous test in statement, which may be given a transcription
+                let mut previous: Option<usize> = None;
+                // Only words in romanized mode have transcriptions
+                let is_romanized = matches!(self.mode_and_name, None | Some((Mode::Romanized, _)));
+
+                // Split at space
+                for word in chars.as_str().split_whitespace() {
+                    let location = location.with_span(span_of(full, word));
+                    let word = word.trim().to_string();
+
+                    // Transcription of previous word, such as `/kato/` in `?+ kato /kato/`
+                    let is_slashed = word.len() > 2 && word.starts_with('/') && word.ends_with('/');
+                    if is_slashed && is_romanized {
+                        if let Some(Test(test)) =
+                            previous.take().map(|index| &mut self.messages[index])
+                        {
+                            test.transcription = Some(word[1..word.len() - 1].to_string());
+                            continue;
+                        }
                     }
 
-                    // Get class pattern
-                    let Some(pattern) = split.next() else {
-                        return parse_error!(line, NoClassPattern, name.to_string());
-                    };
-                    let pattern = pattern.replace(' ', "");
-
-                    // Add class
-                    // Wrap value in NON-CAPTURING GROUP (just in case)
-                    // This is non-capturing, for classes to work with back-references
-                    // otherwise classes would be inherently capturing, and count towards group index in back-reference
-                    raw_classes.insert(
-                        name.trim().to_string(),
-                        (format!("(?:{})", pattern.trim()), line),
-                    );
+                    // Add test
+                    if !word.is_empty() {
+                        self.messages.push(Test(TestDraft {
+                            intent,
+                            word,
+                            reason: reason.clone(),
+                            transcription: None,
+                            underlying: None,
+                        }));
+                        self.test_locations.push(location);
+
+                        if !is_slashed {
+                            previous = Some(self.messages.len() - 1);
+                        }
+                    }
                 }
+            }
 
-                // Rule
-                '+' | '!' => {
-                    // `+` for true, `!` for false
-                    let intent = operator == '+';
-
-                    let pattern = chars.as_str().replace(' ', "");
+            // Note
+            '*' => {
+                let mut note = chars.as_str().trim();
 
-                    // Get most recent note, owned
-                    let note = last_note.clone();
+                if note.is_empty() {
+                    return parse_error!(location.with_span(span_of(full, statement)), EmptyNote);
+                }
 
-                    // Add rule
-                    raw_rules.push(RawRule {
-                        intent,
-                        pattern,
-                        note,
-                        line,
-                    })
+                // Add note if not quiet reason
+                if note.starts_with(':') {
+                    // Quiet reason - don't add note
+                    chars.next();
+                    note = chars.as_str().trim();
+                } else {
+                    // Add note to messages
+                    self.messages.push(Info(Note(note.to_string())));
                 }
 
-                // Test
-                '?' => {
-                    // Remove spaces
-                    while chars.as_str().starts_with(' ') {
-                        chars.next();
+                // Add note, or remove note for following rules, if quiet note is empty
+                self.last_note = if note.is_empty() {
+                    None
+                } else {
+                    Some(Note(note.to_string()))
+                };
+            }
+
+            // Directive
+            // Include directives should have been resolved in `load_statements` function
+            '@' => {
+                let (name, argument) = split_directive(statement).unwrap_or_default();
+
+                match name {
+                    // Backtrack limit of rules
+                    "backtrack-limit" => {
+                        // Fail if limit is already defined
+                        if self.backtrack_limit.is_some() {
+                            return parse_error!(
+                                location.with_span(span_of(full, statement)),
+                                BacktrackLimitAlreadyDefined
+                            );
+                        }
+
+                        self.backtrack_limit = match argument.parse() {
+                            Ok(limit) if limit > 0 => Some(limit),
+                            _ => {
+                                return parse_error!(
+                                    location.with_span(span_of(full, argument)),
+                                    InvalidBacktrackLimit,
+                                    argument.to_string()
+                                )
+                            }
+                        };
                     }
 
-                    // Get intent
-                    let intent = match chars.next() {
-                        // Should be INVALID to pass
-                        Some('+') => true,
-                        // Should be VALID to pass
-                        Some('!') => false,
+                    // Built-in classes
+                    "prelude" => {
+                        if prelude(argument).is_none() {
+                            // Use span of name, or entire statement if name is empty
+                            let span = if argument.is_empty() {
+                                span_of(full, statement)
+                            } else {
+                                span_of(full, argument)
+                            };
+                            return parse_error!(
+                                location.with_span(span),
+                                UnknownPrelude,
+                                argument.to_string()
+                            );
+                        }
 
-                        // Unknown or no character
-                        _ => {
-                            return parse_error!(line, InvalidTestIntent);
+                        if !self.preludes.iter().any(|name| name == argument) {
+                            self.preludes.push(argument.to_string());
                         }
-                    };
+                    }
 
-                    // Split at space
-                    for word in chars.as_str().split_whitespace() {
-                        let word = word.trim().to_string();
+                    // Mapping of grapheme to phoneme
+                    "map" => {
+                        let (grapheme, phoneme) = match parse_mapping(argument) {
+                            Ok(mapping) => mapping,
+                            Err(kind) => {
+                                return Err(Error::Parse(
+                                    kind,
+                                    location.with_span(span_of(full, statement)),
+                                ))
+                            }
+                        };
+
+                        self.orthography.push(Mapping {
+                            grapheme,
+                            phoneme,
+                            location: location.clone(),
+                        });
+                    }
 
-                        // Add test
-                        if !word.is_empty() {
-                            messages.push(Test(TestDraft { intent, word }));
+                    // Allophonic rewrite rule
+                    "allophone" => {
+                        if let Err(kind) = check_allophone(argument) {
+                            return Err(Error::Parse(
+                                kind,
+                                location.with_span(span_of(full, statement)),
+                            ));
                         }
+
+                        self.raw_allophones.push(RawAllophone {
+                            rule: argument.to_string(),
+                            location: location.with_span(span_of(full, argument)),
+                        });
                     }
-                }
 
-                // Note
-                '*' => {
-                    let mut note = chars.as_str().trim();
+                    // Sound change, with the same syntax as an allophone
+                    "change" => {
+                        if let Err(kind) = check_change(argument) {
+                            return Err(Error::Parse(
+                                kind,
+                                location.with_span(span_of(full, statement)),
+                            ));
+                        }
 
-                    if note.is_empty() {
-                        return parse_error!(line, EmptyNote);
+                        self.raw_changes.push(RawChange {
+                            rule: argument.to_string(),
+                            location: location.with_span(span_of(full, argument)),
+                        });
                     }
 
-                    // Add note if not quiet reason
-                    if note.starts_with(':') {
-                        // Quiet reason - don't add note
-                        chars.next();
-                        note = chars.as_str().trim();
-                    } else {
-                        // Add note to messages
-                        messages.push(Info(Note(note.to_string())));
+                    // Features of segments
+                    "features" => {
+                        // Segments are every word before the first feature
+                        let words: Vec<_> = argument.split_whitespace().collect();
+                        let split = words
+                            .iter()
+                            .position(|word| word.starts_with(['+', '-']))
+                            .unwrap_or(words.len());
+                        let (segments, features) = words.split_at(split);
+
+                        if segments.is_empty() {
+                            return parse_error!(
+                                location.with_span(span_of(full, statement)),
+                                NoFeatureSegments
+                            );
+                        }
+                        if features.is_empty() {
+                            return parse_error!(
+                                location.with_span(span_of(full, statement)),
+                                NoFeatures
+                            );
+                        }
+
+                        let features = match parse_features(features) {
+                            Ok(features) => features,
+                            Err(feature) => {
+                                return parse_error!(
+                                    location.with_span(span_of(full, feature)),
+                                    InvalidFeature,
+                                    feature.to_string()
+                                )
+                            }
+                        };
+
+                        self.features.push(FeatureRow {
+                            segments: segments.iter().map(|segment| segment.to_string()).collect(),
+                            features,
+                            location: location.clone(),
+                        });
                     }
 
-                    // Add note
-                    last_note = Some(Note(note.to_string()));
+                    // Unknown directive
+                    _ => {
+                        return parse_error!(
+                            location.with_span(span_of(full, name)),
+                            UnknownDirective,
+                            name.to_string()
+                        );
+                    }
                 }
+            }
 
-                // Unknown line operator
-                _ => {
-                    return parse_error!(line, UnknownStatementOperator, operator);
-                }
+            // Unknown line operator
+            _ => {
+                return parse_error!(
+                    location.with_span(span_of(full, &statement[..operator.len_utf8()])),
+                    UnknownStatementOperator,
+                    operator
+                );
             }
         }
 
-        // Get amount of tests in messages
-        let test_count = messages.iter().filter(|msg| msg.is_test()).count();
+        Ok(())
+    }
 
-        // Use default mode and None name if not specified
-        let (mode, name) = mode_and_name.unwrap_or_default();
+    /// Check whether every tag in condition is selected
+    ///
+    /// Tags beginning with `!` are negated, and must not be selected
+    fn is_condition_met(
+        &self,
+        condition: &str,
+        full: &str,
+        location: &Location,
+    ) -> Result<bool, Error> {
+        if condition.trim().is_empty() {
+            return parse_error!(location.with_span(span_of(full, condition)), EmptyCondition);
+        }
 
-        Ok(Self {
-            rules: parse_rules(&raw_rules, &raw_classes)?,
-            raw_rules,
-            messages,
-            mode,
-            name,
-            test_count,
-            raw_classes,
-        })
+        let mut is_met = true;
+        for tag in condition.split_whitespace() {
+            let (negated, name) = match tag.strip_prefix('!') {
+                Some(name) => (true, name),
+                None => (false, tag),
+            };
+
+            if !regex!(r"^\w+$").is_match(name).expect(REGEX_MATCH_FAIL) {
+                return parse_error!(
+                    location.with_span(span_of(full, tag)),
+                    InvalidTag,
+                    tag.to_string()
+                );
+            }
+
+            // Check every tag, so invalid tags are found even if the condition is not met
+            if self.tags.iter().any(|selected| selected == name) == negated {
+                is_met = false;
+            }
+        }
+
+        Ok(is_met)
     }
 }
 
 /// Parse each rule in list
-fn parse_rules(rules: &[RawRule], classes: &Classes) -> Result<Vec<Rule>, Error> {
+///
+/// Rules which fail to parse are skipped, and the error is added to `errors`
+pub(super) fn parse_rules(
+    rules: &[RawRule],
+    classes: &Classes,
+    macros: &Macros,
+    backtrack_limit: Option<usize>,
+    errors: &mut Vec<Error>,
+) -> Vec<Rule> {
     let mut new = Vec::new();
 
     for RawRule {
         pattern,
         intent,
         note,
-        line,
+        location,
     } in rules
     {
-        new.push(Rule {
-            pattern: parse_regex(pattern, classes, *line)?,
-            intent: *intent,
-            note: note.clone(),
-        })
+        match parse_regex(pattern, classes, macros, backtrack_limit, location) {
+            Ok(regex) => new.push(Rule {
+                pattern: regex,
+                source: pattern.clone(),
+                intent: *intent,
+                note: note.clone(),
+                location: location.clone(),
+            }),
+            Err(err) => errors.push(err),
+        }
     }
 
-    Ok(new)
+    new
 }
 
-/// Substitute class names and parse as regex
-fn parse_regex(pattern: &str, classes: &Classes, line: usize) -> Result<Regex, Error> {
+/// Substitute class names and parse as regex, with an optional backtrack limit
+fn parse_regex(
+    pattern: &str,
+    classes: &Classes,
+    macros: &Macros,
+    backtrack_limit: Option<usize>,
+    location: &Location,
+) -> Result<Regex, Error> {
     // Substitute class names
-    let pattern = replace_classes(pattern, classes, line)?;
+    let pattern = replace_classes(pattern, classes, macros, location)?;
 
     // Parse as regex
-    match Regex::new(&pattern) {
+    let mut builder = RegexBuilder::new(&pattern);
+    if let Some(limit) = backtrack_limit {
+        builder.backtrack_limit(limit);
+    }
+    match builder.build() {
         Ok(regex) => Ok(regex),
-        Err(err) => parse_error!(line, RegexParseFail, err),
+        Err(err) => parse_error!(location, RegexParseFail, err),
     }
 }
 
+/// Check that class name is valid, and that a class or class macro with the name does not already exist
+pub(super) fn check_class_name(
+    name: &str,
+    classes: &Classes,
+    macros: &Macros,
+    location: &Location,
+) -> Result<(), Error> {
+    if !regex!(r"^\w+$").is_match(name).expect(REGEX_MATCH_FAIL) {
+        return parse_error!(location, InvalidClassName, name.to_string());
+    }
+
+    if classes.contains_key(name) || macros.contains_key(name) {
+        return parse_error!(location, ClassAlreadyExists, name.to_string());
+    }
+
+    Ok(())
+}
+
 /// Get first and last character of `Chars`, each as an optional `char`, and remaining (middle) characters, as a `&str`
 fn chars_first_middle_last<'a>(chars: &'a mut Chars) -> (Option<char>, &'a str, Option<char>) {
     let first = chars.next();
@@ -1,4 +1,4 @@
-use crate::error::ParseError;
+use crate::error::{Location, ParseError};
 
 use super::*;
 
@@ -11,13 +11,13 @@ fn parse_rules_works() {
             pattern: "^⟨_⟩+$".to_string(),
             intent: true,
             note: Some(Note("Should contain ⟨a⟩".to_string())),
-            line: 0,
+            location: Default::default(),
         },
         RawRule {
             pattern: "⟨V⟩⟨V⟩".to_string(),
             intent: false,
             note: None,
-            line: 0,
+            location: Default::default(),
         },
     ];
 
@@ -25,17 +25,26 @@ fn parse_rules_works() {
     let rules = vec![
         Rule {
             pattern: Regex::new("^[[ptk][aeiou]]+$").unwrap(),
+            source: "^⟨_⟩+$".to_string(),
             intent: true,
             note: Some(Note("Should contain ⟨a⟩".to_string())),
+            location: Default::default(),
         },
         Rule {
             pattern: Regex::new("[aeiou][aeiou]").unwrap(),
+            source: "⟨V⟩⟨V⟩".to_string(),
             intent: false,
             note: None,
+            location: Default::default(),
         },
     ];
 
-    assert_eq!(parse_rules(&raw_rules, &classes).unwrap(), rules);
+    let mut errors = Vec::new();
+    assert_eq!(
+        parse_rules(&raw_rules, &classes, &Macros::new(), None, &mut errors),
+        rules
+    );
+    assert!(errors.is_empty());
 }
 
 #[test]
@@ -43,32 +52,60 @@ fn parse_regex_works() {
     let classes = example_classes!();
 
     assert_eq!(
-        parse_regex("⟨V⟩", &classes, 0).unwrap().to_string(),
+        parse_regex("⟨V⟩", &classes, &Macros::new(), None, &Default::default())
+            .unwrap()
+            .to_string(),
         "[aeiou]"
     );
     assert_eq!(
-        parse_regex("⟨C⟩", &classes, 0).unwrap().to_string(),
+        parse_regex("⟨C⟩", &classes, &Macros::new(), None, &Default::default())
+            .unwrap()
+            .to_string(),
         "[ptk]"
     );
     assert_eq!(
-        parse_regex("⟨_⟩", &classes, 0).unwrap().to_string(),
+        parse_regex("⟨_⟩", &classes, &Macros::new(), None, &Default::default())
+            .unwrap()
+            .to_string(),
         "[[ptk][aeiou]]"
     );
 
     assert_eq!(
-        parse_regex("⟨V⟩|<C>⟨C⟩", &classes, 0).unwrap().to_string(),
+        parse_regex(
+            "⟨V⟩|<C>⟨C⟩",
+            &classes,
+            &Macros::new(),
+            None,
+            &Default::default()
+        )
+        .unwrap()
+        .to_string(),
         "[aeiou]|[ptk][ptk]"
     );
 
     assert_eq!(
-        parse_regex("^⟨_⟩+$", &classes, 0).unwrap().to_string(),
+        parse_regex(
+            "^⟨_⟩+$",
+            &classes,
+            &Macros::new(),
+            None,
+            &Default::default()
+        )
+        .unwrap()
+        .to_string(),
         "^[[ptk][aeiou]]+$"
     );
 
     assert_eq!(
-        parse_regex(r"(?<a>a)\k<a>|(?P<b><C>)", &classes, 0)
-            .unwrap()
-            .to_string(),
+        parse_regex(
+            r"(?<a>a)\k<a>|(?P<b><C>)",
+            &classes,
+            &Macros::new(),
+            None,
+            &Default::default()
+        )
+        .unwrap()
+        .to_string(),
         r"(?<a>a)\k<a>|(?P<b>[ptk])"
     );
 }
@@ -80,18 +117,17 @@ fn parse_regex_returns_error() {
 
     // Unknown class
     assert!(matches!(
-        parse_regex("⟨E⟩", &classes, 0),
+        parse_regex("⟨E⟩", &classes, &Macros::new(), None, &Default::default()),
         Err(Error::Parse(ParseError::ClassNotFound(..), _))
     ));
 
     // Invalid regex
     assert!(matches!(
-        parse_regex(r"*\", &classes, 0),
+        parse_regex(r"*\", &classes, &Macros::new(), None, &Default::default()),
         Err(Error::Parse(ParseError::RegexParseFail(..), _))
     ));
 }
 
-
 #[test]
 fn chars_first_middle_last_works() {
     let mut chars = "abcd".chars();
@@ -121,4 +157,223 @@ fn chars_first_middle_last_works() {
     assert_eq!(first, Some('a'));
     assert_eq!(mid, "");
     assert_eq!(last, Some('d'));
-}
\ No newline at end of file
+}
+
+#[test]
+fn parse_returns_all_errors() {
+    let file = "~<>\n$C = [ptk]\n$C = [ptk]\n%\n+ ^ <C> <X> $\n?+ taso\n  ?x tako\n+ a(";
+
+    let Err(Error::Multiple(errors)) = Draft::from(file) else {
+        panic!("Should return multiple errors");
+    };
+
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|err| match err {
+            Error::Parse(kind, Location { line, span, .. }) => (kind, *line, span.clone()),
+            _ => panic!("Should be parse error"),
+        })
+        .collect();
+
+    assert_eq!(errors.len(), 5);
+
+    assert!(
+        matches!(errors[0], (ParseError::ClassAlreadyExists(_), 3, Some(ref span)) if *span == (1..2))
+    );
+    assert!(
+        matches!(errors[1], (ParseError::UnknownStatementOperator('%'), 4, Some(ref span)) if *span == (0..1))
+    );
+    assert!(
+        matches!(errors[2], (ParseError::InvalidTestIntent, 7, Some(ref span)) if *span == (3..4))
+    );
+    // Rule errors are returned after statement errors
+    assert!(
+        matches!(errors[3], (ParseError::ClassNotFound(..), 5, Some(ref span)) if *span == (8..11))
+    );
+    assert!(
+        matches!(errors[4], (ParseError::RegexParseFail(_), 8, Some(ref span)) if *span == (2..4))
+    );
+}
+
+#[test]
+fn parse_returns_single_error() {
+    assert!(matches!(
+        Draft::from("~<>\n~//"),
+        Err(Error::Parse(
+            ParseError::ModeAlreadyDefined,
+            Location { line: 2, .. }
+        ))
+    ));
+}
+
+#[test]
+fn parse_returns_cyclic_class_error() {
+    let err = Draft::from("$A = <B>\n$B = x <A>\n+ ^<A>$").unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Parse(ParseError::CyclicClass(_), Location { line: 1, .. })
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Error while parsing: Class definitions are cyclic: <A> (line 1) -> <B> (line 2) -> <A>, at line 1"
+    );
+}
+
+#[test]
+fn parse_backtrack_limit_works() {
+    assert_eq!(
+        Draft::from("@backtrack-limit 500").unwrap().backtrack_limit,
+        Some(500)
+    );
+    assert_eq!(Draft::from("~<>").unwrap().backtrack_limit, None);
+
+    assert!(matches!(
+        Draft::from("@backtrack-limit 0"),
+        Err(Error::Parse(ParseError::InvalidBacktrackLimit(limit), _)) if limit == "0"
+    ));
+    assert!(matches!(
+        Draft::from("@backtrack-limit"),
+        Err(Error::Parse(ParseError::InvalidBacktrackLimit(_), _))
+    ));
+    assert!(matches!(
+        Draft::from("@backtrack-limit 10\n@backtrack-limit 20"),
+        Err(Error::Parse(
+            ParseError::BacktrackLimitAlreadyDefined,
+            Location { line: 2, .. }
+        ))
+    ));
+}
+
+#[test]
+fn parse_test_reason_works() {
+    let draft = Draft::from("?!( Some reason ) abc def\n?! ghi").unwrap();
+
+    assert_eq!(
+        draft.messages,
+        vec![
+            Test(TestDraft {
+                word: "abc".to_string(),
+                intent: false,
+                reason: Some(Note("Some reason".to_string())),
+                transcription: None,
+                underlying: None,
+            }),
+            Test(TestDraft {
+                word: "def".to_string(),
+                intent: false,
+                reason: Some(Note("Some reason".to_string())),
+                transcription: None,
+                underlying: None,
+            }),
+            Test(TestDraft {
+                word: "ghi".to_string(),
+                intent: false,
+                reason: None,
+                transcription: None,
+                underlying: None,
+            }),
+        ]
+    );
+
+    assert!(matches!(
+        Draft::from("?!(Some reason abc"),
+        Err(Error::Parse(ParseError::UnclosedTestReason, Location { span: Some(span), .. })) if span == (2..18)
+    ));
+    assert!(matches!(
+        Draft::from("?!( ) abc"),
+        Err(Error::Parse(ParseError::EmptyTestReason, Location { span: Some(span), .. })) if span == (2..5)
+    ));
+    assert!(matches!(
+        Draft::from("?+(Some reason) abc"),
+        Err(Error::Parse(ParseError::ReasonForValidTest, Location { span: Some(span), .. })) if span == (3..14)
+    ));
+}
+
+#[test]
+fn parse_conditions_works() {
+    let file = "
+$C = [ptk]
+[strict] $V = [aeiou]
+[!strict] $V = [aeiouy]
+[strict] * Strict note
++ ^ (<C><V>)+ $
+[loose !strict] ! y$
+[strict] ?! ky
+?+ kato
+";
+
+    let draft = Draft::from(file).unwrap();
+    assert_eq!(draft.raw_classes["V"].0, "[aeiouy]");
+    assert_eq!(draft.rules.len(), 1);
+    assert_eq!(draft.test_count, 1);
+    assert_eq!(draft.run().fail_count, 0);
+
+    let draft = Draft::from_with_tags(file, &["strict"]).unwrap();
+    assert_eq!(draft.raw_classes["V"].0, "[aeiou]");
+    assert_eq!(draft.rules[0].note, Some(Note("Strict note".to_string())));
+    assert_eq!(draft.test_count, 2);
+    assert_eq!(draft.run().fail_count, 0);
+
+    // Every tag in condition must be met
+    let draft = Draft::from_with_tags(file, &["loose"]).unwrap();
+    assert_eq!(draft.rules.len(), 2);
+    let draft = Draft::from_with_tags(file, &["loose", "strict"]).unwrap();
+    assert_eq!(draft.rules.len(), 1);
+
+    // Minified file only includes selected items
+    assert_eq!(
+        Draft::from_with_tags(file, &["strict"])
+            .unwrap()
+            .minify(true)
+            .unwrap(),
+        "~<>;+^((?:[ptk])(?:[aeiou]))+$;?+kato;?!ky"
+    );
+    assert_eq!(
+        Draft::from_with_tags(file, &["loose"])
+            .unwrap()
+            .minify(true)
+            .unwrap(),
+        "~<>;+^((?:[ptk])(?:[aeiouy]))+$;!y$;?+kato"
+    );
+
+    // Rules after skipped note do not have previous note
+    let file = "* Note A; ! a; [x] * Note B; ! b; ?!(Note A) b";
+    let draft = Draft::from(file).unwrap();
+    assert_eq!(draft.rules[1].note, None);
+    assert_eq!(draft.run().fail_count, 1);
+    let draft = Draft::from_with_tags(file, &["x"]).unwrap();
+    assert_eq!(draft.rules[1].note, Some(Note("Note B".to_string())));
+}
+
+#[test]
+fn parse_conditions_returns_error() {
+    let span_of_error = |file: &str| match Draft::from(file).unwrap_err() {
+        Error::Parse(kind, Location { span, .. }) => (kind.to_string(), span),
+        _ => panic!("Should be parse error"),
+    };
+
+    assert_eq!(
+        span_of_error("[strict + a"),
+        (ParseError::UnclosedCondition.to_string(), Some(0..11))
+    );
+    assert_eq!(
+        span_of_error(" [ ] + a"),
+        (ParseError::EmptyCondition.to_string(), Some(2..3))
+    );
+    assert_eq!(
+        span_of_error("[strict a-b] + a"),
+        (
+            ParseError::InvalidTag("a-b".to_string()).to_string(),
+            Some(8..11)
+        )
+    );
+    assert_eq!(
+        span_of_error("[strict] ~<>"),
+        (ParseError::ConditionNotAllowed.to_string(), Some(0..8))
+    );
+    assert_eq!(
+        span_of_error("[strict] @backtrack-limit 10"),
+        (ParseError::ConditionNotAllowed.to_string(), Some(0..8))
+    );
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests;
+
+use std::path::PathBuf;
+
+use super::{statements::split_statements, Classes};
+use crate::error::Location;
+
+/// Names and files of built-in preludes, which are imported with `@prelude`
+pub(crate) const PRELUDES: &[(&str, &str)] = &[("ipa", include_str!("ipa.phonet"))];
+
+/// Get file of built-in prelude with name, if it exists
+pub(super) fn prelude(name: &str) -> Option<&'static str> {
+    PRELUDES
+        .iter()
+        .find(|(other, _)| *other == name)
+        .map(|(_, file)| *file)
+}
+
+/// Get raw classes of every prelude in list
+///
+/// Locations of classes use the path `<prelude name>`, as preludes are not read from a file
+pub(super) fn prelude_classes(names: &[String]) -> Classes {
+    let mut classes = Classes::new();
+
+    for name in names {
+        let Some(file) = prelude(name) else {
+            continue;
+        };
+        let path = PathBuf::from(format!("<prelude {}>", name));
+
+        // Preludes only contain class definitions and comments
+        for (statement, line, offset) in split_statements(file) {
+            let Some((name, pattern)) = statement
+                .trim()
+                .strip_prefix('$')
+                .and_then(|class| class.split_once('='))
+            else {
+                continue;
+            };
+
+            let location = Location {
+                file: Some(path.clone()),
+                line,
+                offset,
+                span: None,
+            };
+            classes.insert(
+                name.trim().to_string(),
+                (pattern.trim().to_string(), location),
+            );
+        }
+    }
+
+    classes
+}
@@ -0,0 +1,109 @@
+use super::*;
+use crate::{
+    error::{Error, ParseError},
+    Draft, DraftBuilder,
+};
+
+#[test]
+fn prelude_classes_work() {
+    let classes = prelude_classes(&["ipa".to_string()]);
+    assert_eq!(classes["Nasal"].0, "[mɱnɳɲŋɴ]");
+    assert_eq!(
+        classes["Nasal"].1.file,
+        Some(PathBuf::from("<prelude ipa>"))
+    );
+
+    assert!(prelude_classes(&[]).is_empty());
+    assert!(prelude("ipa").is_some());
+    assert!(prelude("xsampa").is_none());
+}
+
+#[test]
+fn ipa_prelude_is_valid() {
+    let draft = Draft::from("@prelude ipa").unwrap();
+
+    // Every class resolves, and can be enumerated
+    for name in prelude_classes(&draft.preludes).keys() {
+        assert!(
+            draft.enumerate_class(name).is_ok(),
+            "Class '{}' should be enumerable",
+            name
+        );
+    }
+
+    // Prelude classes are not raw classes of draft
+    assert!(draft.raw_classes.is_empty());
+    assert!(draft
+        .enumerate_class("Voiced")
+        .unwrap()
+        .contains(&"d͡ʒ".to_string()));
+}
+
+#[test]
+fn prelude_classes_are_used() {
+    let draft = Draft::from(
+        "~[]
+        @prelude ipa
+        $_ = <Consonant> | <Vowel>
+        + ^ <_>+ $
+        ! <Plosive> <Plosive>
+        ?+ kata t͡ʃaŋa
+        ?! kta",
+    )
+    .unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+
+    // Plosives match the first character of affricates, but consonants match affricates as single units
+    let draft = Draft::from("@prelude ipa; ! ^ <Plosive>; ?+ t͡ʃa; ?! ta").unwrap();
+    assert_eq!(draft.run().fail_count, 1);
+    let draft = Draft::from("@prelude ipa; ! ^ <Consonant> <Fricative>; ?+ t͡ʃa; ?! tsa").unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+}
+
+#[test]
+fn classes_shadow_prelude() {
+    let draft = Draft::from("$Nasal = [mn]; @prelude ipa; + ^<Nasal>+$; ?+ mn; ?! ŋ").unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+    assert_eq!(draft.raw_classes.len(), 1);
+
+    // Prelude classes use shadowed class
+    let draft = Draft::from("@prelude ipa; $Mid = [e]; + ^<Vowel>+$; ?+ ea; ?! ə").unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+}
+
+#[test]
+fn prelude_is_minified_and_converted_to_source() {
+    let draft = Draft::from("@prelude ipa; ! <Glottal>").unwrap();
+    assert_eq!(draft.minify(false).unwrap(), "~<>;!(?:[ʔhɦ])");
+    assert_eq!(draft.to_source(), "~<>\n@prelude ipa\n\n! <Glottal>\n");
+
+    let draft = DraftBuilder::new()
+        .prelude("ipa")
+        .prelude("ipa")
+        .rule(false, "<Glottal>")
+        .build()
+        .unwrap();
+    assert_eq!(draft.preludes, vec!["ipa"]);
+}
+
+#[test]
+fn unknown_prelude_returns_error() {
+    assert!(matches!(
+        Draft::from("@prelude ipaa"),
+        Err(Error::Parse(ParseError::UnknownPrelude(name), _)) if name == "ipaa"
+    ));
+    assert!(matches!(
+        Draft::from("@prelude"),
+        Err(Error::Parse(ParseError::UnknownPrelude(name), _)) if name.is_empty()
+    ));
+    assert!(matches!(
+        DraftBuilder::new().prelude("xsampa").build(),
+        Err(Error::Parse(ParseError::UnknownPrelude(_), _))
+    ));
+    assert_eq!(
+        ParseError::UnknownPrelude("ipaa".to_string())
+            .hint()
+            .as_deref(),
+        Some("Did you mean `@prelude ipa`?")
+    );
+}
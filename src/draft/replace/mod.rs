@@ -3,73 +3,303 @@ mod tests;
 
 use fancy_regex_macro::regex;
 
-use crate::{error::Error, REGEX_MATCH_FAIL};
+use std::ops::Range;
 
-use super::Classes;
+use crate::{
+    error::{closest_name, Error, Location, ParseError},
+    REGEX_MATCH_FAIL,
+};
+
+use super::{
+    macros::{referenced_classes, split_call, substitute_parameters, Macros},
+    segments::{segment_list, segment_list_pattern},
+    sets::{resolve_set, set_expression},
+    Classes,
+};
 
 /// Replace ascii `<` and `>` with `⟨` and `⟩` respectively, for classes
 ///
 /// Does not replace `<` and `>` with use in look-behinds or named group definitions or references
 ///
 /// Uses `fancy_regex` `replace_all` method, with with capture preservation
-fn replace_angle_brackets(pattern: &str) -> String {
+pub(crate) fn replace_angle_brackets(pattern: &str) -> String {
     regex!(r"(?<!\(\?)(?<!\(\?P)(?<!\\k)<([^>]*)>")
         .replace_all(pattern, r"⟨$1⟩")
         .to_string()
 }
 
-/// Substitute class names regex rule with class values (recursively)
+/// Substitute class names in each class value, and wrap each value in a non-capturing group
+///
+/// Classes are resolved in order of dependency, so each class is only expanded once
+///
+/// `bundles` are resolved feature bundles (see `resolve_bundles`), which are included in the result
+///
+/// Classes which are cyclic, or which fail to resolve, are given an empty value, and the error is added to `errors`
+pub(crate) fn resolve_classes(
+    raw_classes: &Classes,
+    macros: &Macros,
+    bundles: Classes,
+    errors: &mut Vec<Error>,
+) -> Classes {
+    let mut resolved = bundles;
+
+    // Resolve in order of definition, for consistent order of errors
+    let mut names: Vec<_> = raw_classes.iter().collect();
+    names.sort_by_key(|(_, (_, location))| (location.file.clone(), location.offset));
+
+    for (name, _) in names {
+        resolve_class(
+            name,
+            raw_classes,
+            macros,
+            &mut resolved,
+            &mut Vec::new(),
+            errors,
+        );
+    }
+
+    resolved
+}
+
+/// Resolve a single class, after resolving every class which it references (recursively)
 ///
-/// `pattern` argument must not contain spaces
+/// `stack` holds the names of every class currently being resolved, to detect cyclic classes
+fn resolve_class(
+    name: &str,
+    raw_classes: &Classes,
+    macros: &Macros,
+    resolved: &mut Classes,
+    stack: &mut Vec<String>,
+    errors: &mut Vec<Error>,
+) {
+    // Class is already resolved
+    if resolved.contains_key(name) {
+        return;
+    }
+
+    // Missing classes are reported when class name is replaced
+    let Some((value, location)) = raw_classes.get(name) else {
+        return;
+    };
+
+    // Class is already being resolved - Class is cyclic
+    if let Some(index) = stack.iter().position(|other| other == name) {
+        let cycle = &stack[index..];
+
+        errors.push(Error::Parse(
+            ParseError::CyclicClass(
+                cycle
+                    .iter()
+                    .map(|name| (name.clone(), raw_classes[name].1.clone()))
+                    .collect(),
+            ),
+            location.clone(),
+        ));
+
+        // Give every class in cycle an empty value
+        for name in cycle {
+            resolved.insert(
+                name.clone(),
+                (String::from("(?:)"), raw_classes[name].1.clone()),
+            );
+        }
+        return;
+    }
+
+    // Resolve every referenced class first, including classes used by class macros
+    stack.push(name.to_string());
+    for (reference, _) in class_references(value) {
+        for name in referenced_classes(&reference, macros, &mut Vec::new()) {
+            resolve_class(&name, raw_classes, macros, resolved, stack, errors);
+        }
+    }
+    stack.pop();
+
+    // Class was resolved as part of a cycle
+    if resolved.contains_key(name) {
+        return;
+    }
+
+    let value = match set_expression(value) {
+        // Resolve set operation between classes to segments
+        Some(expression) => resolve_set(&expression, value, resolved, macros, location),
+
+        None => {
+            // Convert segment list to pattern, before spaces are removed
+            let value = match segment_list(value) {
+                Some(items) => segment_list_pattern(&items, raw_classes),
+                None => value.clone(),
+            };
+            replace_classes(&value, resolved, macros, location)
+        }
+    };
+
+    // Wrap value in NON-CAPTURING GROUP (just in case)
+    // This is non-capturing, for classes to work with back-references
+    // otherwise classes would be inherently capturing, and count towards group index in back-reference
+    let value = match value {
+        Ok(value) => format!("(?:{})", value),
+        Err(err) => {
+            errors.push(err);
+            String::from("(?:)")
+        }
+    };
+
+    resolved.insert(name.to_string(), (value, location.clone()));
+}
+
+/// Get names of every class referenced in pattern, with the range of characters in `pattern` of each
+///
+/// Invalid class names are included, and unfinished class names are ignored
+pub(super) fn class_references(pattern: &str) -> Vec<(String, Range<usize>)> {
+    // Remove spaces, keeping original character index of each character
+    let (indexes, stripped): (Vec<usize>, String) = pattern
+        .chars()
+        .enumerate()
+        .filter(|(_, ch)| *ch != ' ')
+        .unzip();
+    let chars: Vec<char> = replace_angle_brackets(&stripped).chars().collect();
+
+    let mut references = Vec::new();
+    // Index of opening bracket of current class name
+    let mut start = None;
+
+    for (i, ch) in chars.iter().enumerate() {
+        match ch {
+            '⟨' => start = Some(i),
+            '⟩' => {
+                if let Some(start) = start.take() {
+                    references.push((
+                        chars[start + 1..i].iter().collect(),
+                        indexes[start]..indexes[i] + 1,
+                    ));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    references
+}
+
+/// Substitute class names regex rule with class values
+///
+/// `classes` must be resolved with `resolve_classes`, as class values are not substituted recursively.
+/// Class macros are expanded with their arguments
+///
+/// Spaces in `pattern` are removed
+///
+/// Spans of errors are relative to the span of `location`
 pub(crate) fn replace_classes(
     pattern: &str,
     classes: &Classes,
-    line: usize,
+    macros: &Macros,
+    location: &Location,
 ) -> Result<String, Error> {
+    replace(pattern, classes, macros, &mut Vec::new())
+        .map_err(|(kind, span)| Error::Parse(kind, location.sub_span(span)))
+}
+
+/// Substitute class names regex rule with class values
+///
+/// Returns the kind of error, and the range of characters in `pattern` where it occurred
+///
+/// `stack` holds the names of every class macro currently being expanded, to detect recursive macros
+fn replace(
+    pattern: &str,
+    classes: &Classes,
+    macros: &Macros,
+    stack: &mut Vec<String>,
+) -> Result<String, (ParseError, Range<usize>)> {
+    // Remove spaces, keeping original character index of each character
+    let (indexes, stripped): (Vec<usize>, String) = pattern
+        .chars()
+        .enumerate()
+        .filter(|(_, ch)| *ch != ' ')
+        .unzip();
+    // Get range of original characters, from range of characters without spaces
+    let span = |start: usize, end: usize| match indexes.get(start..end) {
+        Some([first, .., last]) => *first..*last + 1,
+        Some([only]) => *only..*only + 1,
+        _ => pattern.chars().count()..pattern.chars().count(),
+    };
+
     // Replace `<` and `>` with `⟨` and `⟩` respectively, where classes are
-    let pattern = replace_angle_brackets(pattern);
+    // This does not change the amount of characters
+    let stripped = replace_angle_brackets(&stripped);
 
     // Return string
     let mut output = String::new();
 
-    // Build class name
-    let mut name_build: Option<String> = None;
+    // Build class name, with index of opening bracket
+    let mut name_build: Option<(String, usize)> = None;
+
+    // Amount of nested square brackets in output, and whether previous character was an escape
+    let mut brackets = 0usize;
+    let mut is_escaped = false;
 
     // Loop characters
-    for ch in pattern.chars() {
+    for (i, ch) in stripped.chars().enumerate() {
         match ch {
             // Open class name
             '⟨' => {
                 if name_build.is_some() {
                     // Name is already building - Another opening bracket should not be there
-                    return parse_error!(line, UnexpectedClassNameOpen);
+                    return Err((ParseError::UnexpectedClassNameOpen, span(i, i + 1)));
                 }
 
                 // Start building name
-                name_build = Some(String::new());
+                name_build = Some((String::new(), i));
             }
 
             // Close class name
             '⟩' => {
                 // Get class name
-                let Some(name) = name_build else {
+                let Some((name, start)) = name_build else {
                     // No name is building - Closing bracket should not be there
-                    return parse_error!(line, UnexpectedClassNameClose);
+                    return Err((ParseError::UnexpectedClassNameClose, span(i, i + 1)));
                 };
+                let name_span = span(start, i + 1);
 
-                // Check if name is valid
-                if !regex!(r"^\w+$").is_match(&name).expect(REGEX_MATCH_FAIL) {
-                    return parse_error!(line, InvalidClassName, name);
+                // Expand class macro with arguments
+                if let Some((name, arguments)) = split_call(&name) {
+                    let value = expand_macro(name, &arguments, classes, macros, stack)
+                        .map_err(|kind| (kind, name_span))?;
+                    output.push_str(&value);
+                    name_build = None;
+                    continue;
+                }
+
+                // Check if name is valid, or is a feature bundle
+                if !regex!(r"^\w+$|^\[.*\]$")
+                    .is_match(&name)
+                    .expect(REGEX_MATCH_FAIL)
+                {
+                    return Err((ParseError::InvalidClassName(name), name_span));
                 }
 
                 // Get class value
-                let Some((value, _line)) = classes.get(&name) else {
+                let Some((value, _location)) = classes.get(&name) else {
+                    // Class macro was used without arguments
+                    if let Some(class_macro) = macros.get(&name) {
+                        let count = class_macro.parameters.len();
+                        return Err((ParseError::ClassArgumentCount(name, count, 0), name_span));
+                    }
+
                     // Class name was not found
-                    return parse_error!(line, ClassNotFound, name);
+                    let closest = closest_name(&name, class_names(classes, macros));
+                    return Err((
+                        ParseError::ClassNotFound(name, closest.map(str::to_string)),
+                        name_span,
+                    ));
                 };
 
-                // Add value to output (recursively)
-                output.push_str(&replace_classes(value, classes, line)?);
+                // Add value to output
+                // Inside square brackets, class is added as a set of characters, if possible, so `[<C><V>]` is a union
+                match brackets {
+                    0 => output.push_str(value),
+                    _ => output.push_str(&bracket_item(value).unwrap_or_else(|| value.clone())),
+                }
 
                 // Finish building and reset
                 name_build = None;
@@ -79,18 +309,191 @@ pub(crate) fn replace_classes(
             _ => {
                 match &mut name_build {
                     // Name is building - push to name
-                    Some(name) => name.push(ch),
+                    Some((name, _)) => name.push(ch),
                     // Name is not building - push to regular output
-                    None => output.push(ch),
+                    None => {
+                        match ch {
+                            _ if is_escaped => is_escaped = false,
+                            '\\' => is_escaped = true,
+                            '[' => brackets += 1,
+                            ']' if brackets > 0 => brackets -= 1,
+                            _ => (),
+                        }
+                        output.push(ch)
+                    }
                 }
             }
         }
     }
 
     // Class name was not finished building, before end of end of pattern
-    if name_build.is_some() {
-        return parse_error!(line, UnexpectedPatternEnd);
+    if let Some((_, start)) = name_build {
+        return Err((
+            ParseError::UnexpectedPatternEnd,
+            span(start, stripped.chars().count()),
+        ));
     }
 
     Ok(output)
 }
+
+/// Convert resolved class value to an item of a bracket class, such as `[ptk]` for `(?:[ptk])`, or `ab` for `(?:a|b)`
+///
+/// Returns `None` if value does not match only single characters, such as a class with a digraph
+fn bracket_item(value: &str) -> Option<String> {
+    let mut value = value;
+    while let Some(inner) = strip_group(value) {
+        value = inner;
+    }
+
+    // Single bracket class
+    if value.starts_with('[') && closing_bracket(value) == Some(value.len() - 1) {
+        return Some(value.to_string());
+    }
+
+    // Alternation of items
+    let alternatives = split_alternatives(value);
+    if alternatives.len() > 1 {
+        return alternatives.into_iter().map(bracket_item).collect();
+    }
+
+    // Single character
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if !"()[]{}|.*+?^$\\-&~".contains(ch) => Some(ch.to_string()),
+        _ => None,
+    }
+}
+
+/// Split value at every `|` which is not in a group or bracket class
+fn split_alternatives(value: &str) -> Vec<&str> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    let mut chars = value.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '|' if depth == 0 => {
+                alternatives.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    alternatives.push(&value[start..]);
+    alternatives
+}
+
+/// Remove non-capturing or atomic group around entire value, such as `(?:...)` or `(?>...)`
+fn strip_group(value: &str) -> Option<&str> {
+    let inner = value
+        .strip_prefix("(?:")
+        .or_else(|| value.strip_prefix("(?>"))?
+        .strip_suffix(')')?;
+
+    // Opening parenthesis must be closed by the last character, not earlier
+    let mut depth = 0usize;
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '(' => depth += 1,
+            ')' if depth == 0 => return None,
+            ')' => depth -= 1,
+            _ => (),
+        }
+    }
+    (depth == 0).then_some(inner)
+}
+
+/// Get byte index of bracket which closes the bracket class at the start of value
+fn closing_bracket(value: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut chars = value.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\\' => {
+                chars.next();
+            }
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Expand class macro with arguments, such as `<Geminate(C)>`
+///
+/// Errors within the pattern of the macro are returned without a span
+fn expand_macro(
+    name: &str,
+    arguments: &[&str],
+    classes: &Classes,
+    macros: &Macros,
+    stack: &mut Vec<String>,
+) -> Result<String, ParseError> {
+    let Some(class_macro) = macros.get(name) else {
+        // Class without parameters was given arguments
+        if classes.contains_key(name) {
+            return Err(ParseError::ClassArgumentCount(
+                name.to_string(),
+                0,
+                arguments.len(),
+            ));
+        }
+
+        let closest = closest_name(name, class_names(classes, macros));
+        return Err(ParseError::ClassNotFound(
+            name.to_string(),
+            closest.map(str::to_string),
+        ));
+    };
+
+    // Check arguments
+    if arguments.len() != class_macro.parameters.len() {
+        return Err(ParseError::ClassArgumentCount(
+            name.to_string(),
+            class_macro.parameters.len(),
+            arguments.len(),
+        ));
+    }
+    if let Some(argument) = arguments
+        .iter()
+        .find(|argument| !regex!(r"^\w+$").is_match(argument).expect(REGEX_MATCH_FAIL))
+    {
+        return Err(ParseError::InvalidClassArgument(argument.to_string()));
+    }
+
+    // Macro is already being expanded
+    if stack.iter().any(|other| other == name) {
+        return Err(ParseError::CyclicMacro(name.to_string()));
+    }
+
+    let pattern = substitute_parameters(&class_macro.pattern, &class_macro.parameters, arguments);
+
+    stack.push(name.to_string());
+    let value = replace(&pattern, classes, macros, stack).map_err(|(kind, _)| kind);
+    stack.pop();
+
+    // Wrap value in non-capturing group, the same as classes
+    Ok(format!("(?:{})", value?))
+}
+
+/// Get names of every class and class macro
+fn class_names<'a>(classes: &'a Classes, macros: &'a Macros) -> impl Iterator<Item = &'a str> {
+    classes.keys().chain(macros.keys()).map(String::as_str)
+}
@@ -23,32 +23,38 @@ fn replace_classes_works() {
     let classes = example_classes!();
 
     assert_eq!(
-        replace_classes("<C>", &classes, 0).unwrap(),
+        replace_classes("<C>", &classes, &Macros::new(), &Default::default()).unwrap(),
         "[ptk]".to_string()
     );
 
     assert_eq!(
-        replace_classes("<C>-<V>", &classes, 0).unwrap(),
+        replace_classes("<C>-<V>", &classes, &Macros::new(), &Default::default()).unwrap(),
         "[ptk]-[aeiou]".to_string()
     );
 
     assert_eq!(
-        replace_classes("<_>", &classes, 0).unwrap(),
+        replace_classes("<_>", &classes, &Macros::new(), &Default::default()).unwrap(),
         "[[ptk][aeiou]]".to_string()
     );
 
     assert_eq!(
-        replace_classes("(?<=1)", &classes, 0).unwrap(),
+        replace_classes("(?<=1)", &classes, &Macros::new(), &Default::default()).unwrap(),
         "(?<=1)".to_string()
     );
 
     assert_eq!(
-        replace_classes("(?<abc><C>)", &classes, 0).unwrap(),
+        replace_classes("(?<abc><C>)", &classes, &Macros::new(), &Default::default()).unwrap(),
         "(?<abc>[ptk])".to_string()
     );
 
-    assert_eq!(replace_classes("a>b", &classes, 0).unwrap(), "a>b");
-    assert_eq!(replace_classes("a<b", &classes, 0).unwrap(), "a<b");
+    assert_eq!(
+        replace_classes("a>b", &classes, &Macros::new(), &Default::default()).unwrap(),
+        "a>b"
+    );
+    assert_eq!(
+        replace_classes("a<b", &classes, &Macros::new(), &Default::default()).unwrap(),
+        "a<b"
+    );
 }
 
 #[test]
@@ -56,12 +62,140 @@ fn replace_classes_returns_error() {
     let classes = example_classes!();
 
     assert!(matches!(
-        replace_classes("<c>", &classes, 0),
+        replace_classes("<c>", &classes, &Macros::new(), &Default::default()),
         Err(Error::Parse(ParseError::ClassNotFound(..), _))
     ));
 
     assert!(matches!(
-        replace_classes("<a<b>c>", &classes, 0),
+        replace_classes("<a<b>c>", &classes, &Macros::new(), &Default::default()),
         Err(Error::Parse(ParseError::InvalidClassName(..), _))
     ));
 }
+
+#[test]
+fn replace_classes_returns_error_span() {
+    let classes = example_classes!();
+    let location = Location::default().with_span(4..20);
+
+    assert!(matches!(
+        replace_classes("^ <C> <Xyz>+ $", &classes, &Macros::new(), &location),
+        Err(Error::Parse(ParseError::ClassNotFound(..), Location { span: Some(span), .. }))
+            if span == (10..15)
+    ));
+
+    assert!(matches!(
+        replace_classes("a ⟨b ⟨c⟩", &classes, &Macros::new(), &location),
+        Err(Error::Parse(ParseError::UnexpectedClassNameOpen, Location { span: Some(span), .. }))
+            if span == (9..10)
+    ));
+
+    assert!(matches!(
+        replace_classes("a ⟨C", &classes, &Macros::new(), &location),
+        Err(Error::Parse(ParseError::UnexpectedPatternEnd, Location { span: Some(span), .. }))
+            if span == (6..8)
+    ));
+}
+
+/// Create raw classes from names and values, with line numbers in order
+fn raw_classes(classes: &[(&str, &str)]) -> Classes {
+    classes
+        .iter()
+        .enumerate()
+        .map(|(line, (name, value))| {
+            (
+                name.to_string(),
+                (
+                    value.to_string(),
+                    Location {
+                        line: line + 1,
+                        offset: line,
+                        ..Default::default()
+                    },
+                ),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn class_references_works() {
+    let names = |pattern| -> Vec<String> {
+        class_references(pattern)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    };
+
+    assert_eq!(names("[ptk]"), Vec::<String>::new());
+    assert_eq!(names("<C> | ⟨V⟩"), vec!["C", "V"]);
+    assert_eq!(names("(?<x><C>)\\k<x>"), vec!["C"]);
+    assert_eq!(names("<C"), Vec::<String>::new());
+    assert_eq!(names("<[+voice -nasal]>"), vec!["[+voice-nasal]"]);
+
+    // Span of original characters
+    assert_eq!(
+        class_references("a < C >b⟨V⟩"),
+        vec![("C".to_string(), 2..7), ("V".to_string(), 8..11)]
+    );
+}
+
+#[test]
+fn resolve_classes_works() {
+    let raw = raw_classes(&[
+        ("_", "[ <C> ⟨V⟩ ]"),
+        ("V", "[aeiou]"),
+        ("C", "<Stop> | [mn]"),
+        ("Stop", "[ptk]"),
+    ]);
+
+    let mut errors = Vec::new();
+    let classes = resolve_classes(&raw, &Macros::new(), Classes::new(), &mut errors);
+
+    assert!(errors.is_empty());
+    assert_eq!(classes["Stop"].0, "(?:[ptk])");
+    assert_eq!(classes["C"].0, "(?:(?:[ptk])|[mn])");
+    // Classes inside brackets are a union
+    assert_eq!(classes["_"].0, "(?:[[ptk][mn][aeiou]])");
+    assert_eq!(classes["_"].1.line, 1);
+}
+
+#[test]
+fn resolve_classes_returns_error() {
+    let raw = raw_classes(&[
+        ("A", "<B>"),
+        ("B", "x <C>"),
+        ("C", "<A>"),
+        ("D", "<A>"),
+        ("E", "<E>"),
+        ("F", "<Missing>"),
+    ]);
+
+    let mut errors = Vec::new();
+    let classes = resolve_classes(&raw, &Macros::new(), Classes::new(), &mut errors);
+
+    assert_eq!(errors.len(), 3);
+
+    // Cycle of multiple classes
+    let Error::Parse(ParseError::CyclicClass(cycle), Location { line: 1, .. }) = &errors[0] else {
+        panic!("Should be cyclic class error");
+    };
+    let cycle: Vec<_> = cycle
+        .iter()
+        .map(|(name, location)| (name.as_str(), location.line))
+        .collect();
+    assert_eq!(cycle, vec![("A", 1), ("B", 2), ("C", 3)]);
+
+    // Class which references itself
+    assert!(matches!(
+        &errors[1],
+        Error::Parse(ParseError::CyclicClass(cycle), Location { line: 5, .. }) if cycle.len() == 1
+    ));
+
+    assert!(matches!(
+        &errors[2],
+        Error::Parse(ParseError::ClassNotFound(..), Location { line: 6, .. })
+    ));
+
+    // Classes which depend on a cyclic class are still resolved
+    assert_eq!(classes["D"].0, "(?:(?:))");
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests;
+
+use super::Classes;
+
+/// Get segments of class pattern, if it is a segment list
+///
+/// A segment list is written in curly brackets, with segments separated by whitespace, such as `{p t k ng ch}`
+pub(super) fn segment_list(pattern: &str) -> Option<Vec<&str>> {
+    let inner = pattern.trim().strip_prefix('{')?.strip_suffix('}')?;
+    Some(inner.split_whitespace().collect())
+}
+
+/// Convert items of segment list to regex pattern, which matches each segment as a single unit
+///
+/// References to other segment lists are flattened, and segments are sorted longest first, in an atomic group.
+/// This means that a longer segment (such as `ng`) is always matched instead of a shorter one (such as `n`)
+///
+/// References to classes which are not segment lists are kept, after every segment, to be substituted later
+pub(super) fn segment_list_pattern(items: &[&str], raw_classes: &Classes) -> String {
+    let mut segments = Vec::new();
+    let mut references = Vec::new();
+    flatten_segments(
+        items,
+        raw_classes,
+        &mut Vec::new(),
+        &mut segments,
+        &mut references,
+    );
+
+    segments_pattern(segments, &references)
+}
+
+/// Convert segments to regex pattern, in an atomic group, with segments sorted longest first
+///
+/// Segments are escaped, and class references are added after every segment
+pub(super) fn segments_pattern(mut segments: Vec<String>, references: &[&str]) -> String {
+    // Longest first, keeping order of segments with the same length
+    segments.sort_by_key(|segment| std::cmp::Reverse(segment.chars().count()));
+
+    let alternatives: Vec<_> = segments
+        .iter()
+        .map(|segment| fancy_regex::escape(segment).to_string())
+        .chain(references.iter().map(|reference| reference.to_string()))
+        .collect();
+
+    format!("(?>{})", alternatives.join("|"))
+}
+
+/// Add segments of segment list to `segments`, including segments of referenced segment lists (recursively)
+///
+/// `visited` holds the names of every segment list already flattened, so cyclic references do not repeat
+fn flatten_segments<'a>(
+    items: &[&'a str],
+    raw_classes: &'a Classes,
+    visited: &mut Vec<&'a str>,
+    segments: &mut Vec<String>,
+    references: &mut Vec<&'a str>,
+) {
+    for item in items {
+        let Some(name) = class_reference(item) else {
+            // Literal segment
+            if !segments.iter().any(|segment| segment == item) {
+                segments.push(item.to_string());
+            }
+            continue;
+        };
+
+        // Flatten referenced segment list
+        let list = raw_classes
+            .get_key_value(name)
+            .and_then(|(name, (pattern, _))| Some((name.as_str(), segment_list(pattern)?)));
+        match list {
+            Some((name, items)) => {
+                if !visited.contains(&name) {
+                    visited.push(name);
+                    flatten_segments(&items, raw_classes, visited, segments, references);
+                }
+            }
+            // Other class, or missing class
+            None => references.push(item),
+        }
+    }
+}
+
+/// Get name of class, if item is a class reference, such as `<C>`
+fn class_reference(item: &str) -> Option<&str> {
+    item.strip_prefix('<')
+        .and_then(|item| item.strip_suffix('>'))
+        .or_else(|| item.strip_prefix('⟨')?.strip_suffix('⟩'))
+}
@@ -0,0 +1,63 @@
+use super::*;
+use crate::Draft;
+
+/// Classes with the given patterns, without locations
+fn classes(patterns: &[(&str, &str)]) -> Classes {
+    patterns
+        .iter()
+        .map(|(name, pattern)| (name.to_string(), (pattern.to_string(), Default::default())))
+        .collect()
+}
+
+#[test]
+fn segment_list_works() {
+    assert_eq!(segment_list("{p t  ng}"), Some(vec!["p", "t", "ng"]));
+    assert_eq!(segment_list(" { } "), Some(vec![]));
+    assert_eq!(segment_list("[ptk]"), None);
+    assert_eq!(segment_list("{p t"), None);
+}
+
+#[test]
+fn segment_list_pattern_works() {
+    let classes = classes(&[
+        ("C", "{p ng}"),
+        ("Nasal", "{m n ŋ}"),
+        ("V", "[aeiou]"),
+        ("Cyclic", "{x <Cyclic>}"),
+    ]);
+
+    assert_eq!(
+        segment_list_pattern(&["t", "ch", "tsh", "t"], &classes),
+        "(?>tsh|ch|t)"
+    );
+    assert_eq!(
+        segment_list_pattern(&["<C>", "<V>", "⟨Nasal⟩", "a.b"], &classes),
+        r"(?>a\.b|ng|p|m|n|ŋ|<V>)"
+    );
+    assert_eq!(segment_list_pattern(&["<Cyclic>"], &classes), "(?>x)");
+}
+
+#[test]
+fn segments_match_as_units() {
+    let draft = Draft::from(
+        "
+$C = {n g ng k}
+$V = {a i}
++ ^ (<C><V>?)+ $
+! <C>{3}
+?+ ngka kanga ngkangka
+?! nngka ngaa
+",
+    )
+    .unwrap();
+
+    // `ngk` is two segments, but `nngk` is three
+    assert_eq!(draft.run().fail_count, 0);
+    assert_eq!(
+        Draft::from("$C = {n g ng k}; ! <C>{3}; ?! nngk; ?+ ngk")
+            .unwrap()
+            .run()
+            .fail_count,
+        0
+    );
+}
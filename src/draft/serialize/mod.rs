@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+
+use fancy_regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    allophony::RawAllophone, changes::RawChange, macros::ClassMacro, Draft, DraftBuilder,
+    FeatureRow, Mapping, Message, Mode, Note, RawRule, Rule, TestDraft,
+};
+use crate::error::Location;
+
+/// Serialized form of `Rule`
+///
+/// The compiled regex is stored as both the source pattern and the expanded pattern
+#[derive(Serialize, Deserialize)]
+struct RuleData {
+    /// Regex pattern as written in file
+    pattern: String,
+    /// Regex pattern, with classes substituted
+    expanded: String,
+    intent: bool,
+    note: Option<Note>,
+    location: Location,
+}
+
+/// Serialized form of a single class of `Draft`
+#[derive(Serialize, Deserialize)]
+struct ClassData {
+    /// Pattern as written in file
+    pattern: String,
+    /// Pattern with classes substituted
+    ///
+    /// Only used for reading, classes are resolved again when deserialized
+    #[serde(default, skip_deserializing)]
+    expanded: String,
+    location: Location,
+}
+
+/// Serialized form of `Draft`
+#[derive(Serialize, Deserialize)]
+struct DraftData {
+    name: Option<String>,
+    mode: Mode,
+    /// Only used for reading, tests are counted again when deserialized
+    #[serde(default, skip_deserializing)]
+    test_count: usize,
+    backtrack_limit: Option<usize>,
+    /// Sorted by name, so output is deterministic
+    classes: BTreeMap<String, ClassData>,
+    /// Sorted by name, so output is deterministic
+    #[serde(default)]
+    macros: BTreeMap<String, ClassMacro>,
+    #[serde(default)]
+    preludes: Vec<String>,
+    #[serde(default)]
+    features: Vec<FeatureRow>,
+    #[serde(default)]
+    orthography: Vec<Mapping>,
+    #[serde(default)]
+    allophones: Vec<RawAllophone>,
+    #[serde(default)]
+    changes: Vec<RawChange>,
+    rules: Vec<RuleData>,
+    messages: Vec<Message<TestDraft>>,
+}
+
+impl From<&Rule> for RuleData {
+    fn from(rule: &Rule) -> Self {
+        Self {
+            pattern: rule.source.clone(),
+            expanded: rule.pattern.as_str().to_string(),
+            intent: rule.intent,
+            note: rule.note.clone(),
+            location: rule.location.clone(),
+        }
+    }
+}
+
+impl Serialize for Rule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RuleData::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rule {
+    /// Regex is compiled from the expanded pattern
+    ///
+    /// The backtrack limit of the draft is not stored with a single rule, so the default limit is used.
+    /// Deserialize the entire `Draft` to keep the limit given with `@backtrack-limit`
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RuleData {
+            pattern,
+            expanded,
+            intent,
+            note,
+            location,
+        } = RuleData::deserialize(deserializer)?;
+
+        Ok(Self {
+            pattern: Regex::new(&expanded).map_err(de::Error::custom)?,
+            source: pattern,
+            intent,
+            note,
+            location,
+        })
+    }
+}
+
+impl Serialize for Draft {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let classes = self
+            .raw_classes
+            .iter()
+            .map(|(name, (pattern, location))| {
+                let class = ClassData {
+                    pattern: pattern.clone(),
+                    expanded: self
+                        .classes
+                        .get(name)
+                        .map(|(expanded, _)| expanded.clone())
+                        .unwrap_or_default(),
+                    location: location.clone(),
+                };
+                (name.clone(), class)
+            })
+            .collect();
+
+        DraftData {
+            name: self.name.clone(),
+            mode: self.mode,
+            test_count: self.test_count,
+            backtrack_limit: self.backtrack_limit,
+            classes,
+            macros: self
+                .macros
+                .iter()
+                .map(|(name, class_macro)| (name.clone(), class_macro.clone()))
+                .collect(),
+            preludes: self.preludes.clone(),
+            features: self.features.clone(),
+            orthography: self.orthography.clone(),
+            allophones: self.raw_allophones.clone(),
+            changes: self.raw_changes.clone(),
+            rules: self.rules.iter().map(RuleData::from).collect(),
+            messages: self.messages.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Draft {
+    /// Classes are resolved, and rules are compiled, from their source patterns
+    ///
+    /// This is so the draft is identical to one parsed from a file, including for `Draft::minify`
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let DraftData {
+            name,
+            mode,
+            backtrack_limit,
+            classes,
+            macros,
+            preludes,
+            features,
+            orthography,
+            allophones,
+            changes,
+            rules,
+            messages,
+            ..
+        } = DraftData::deserialize(deserializer)?;
+
+        let raw_classes = classes
+            .into_iter()
+            .map(|(name, ClassData { pattern, location, .. })| (name, (pattern, location)))
+            .collect();
+
+        let raw_rules = rules
+            .into_iter()
+            .map(|rule| RawRule {
+                pattern: rule.pattern,
+                intent: rule.intent,
+                note: rule.note,
+                location: rule.location,
+            })
+            .collect();
+
+        // Compile classes and rules, the same as a `DraftBuilder`
+        DraftBuilder {
+            messages,
+            name,
+            mode,
+            backtrack_limit,
+            raw_rules,
+            raw_classes,
+            macros: macros.into_iter().collect(),
+            preludes,
+            features,
+            orthography,
+            raw_allophones: allophones,
+            raw_changes: changes,
+            ..Default::default()
+        }
+        .build()
+        .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,112 @@
+use super::*;
+use crate::outcome::{EvalMode, Outcome};
+
+/// Example draft with classes, rules, notes and tests
+const EXAMPLE: &str = "
+~<Example>
+@backtrack-limit 1000
+$C = [ptk]
+$V = [aeiou]
+$_ = ⟨C⟩ | ⟨V⟩
+* Invalid letters
+  + ^ ⟨_⟩+ $
+* Vowel clusters
+  ! ⟨V⟩{2}
+?+ kato
+?!(Vowel clusters) taeko
+";
+
+#[test]
+fn rule_round_trip_works() {
+    let draft = Draft::from(EXAMPLE).unwrap();
+    let rule = &draft.rules[1];
+
+    let json = serde_json::to_value(rule).unwrap();
+    assert_eq!(json["pattern"], "⟨V⟩{2}");
+    assert_eq!(json["expanded"], rule.pattern.as_str());
+    assert_eq!(json["intent"], false);
+    assert_eq!(json["note"], "Vowel clusters");
+
+    let parsed: Rule = serde_json::from_value(json).unwrap();
+    assert_eq!(&parsed, rule);
+    assert_eq!(parsed.source, rule.source);
+    assert_eq!(parsed.location, rule.location);
+}
+
+#[test]
+fn rule_invalid_pattern_fails() {
+    let json = serde_json::json!({
+        "pattern": "(",
+        "expanded": "(",
+        "intent": true,
+        "note": null,
+        "location": { "file": null, "line": 1, "offset": 0, "span": null },
+    });
+
+    assert!(serde_json::from_value::<Rule>(json).is_err());
+}
+
+#[test]
+fn draft_round_trip_works() {
+    let draft = Draft::from(EXAMPLE).unwrap();
+
+    let json = serde_json::to_string(&draft).unwrap();
+    let parsed: Draft = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed, draft);
+    assert_eq!(parsed.backtrack_limit, Some(1000));
+    assert_eq!(parsed.minify(true).unwrap(), draft.minify(true).unwrap());
+    assert_eq!(
+        parsed.run_with(EvalMode::AllViolations).messages,
+        draft.run_with(EvalMode::AllViolations).messages
+    );
+}
+
+#[test]
+fn draft_features_round_trip_works() {
+    let draft = Draft::from("@features p t -voice; @features b d +voice; ! <[+voice]>").unwrap();
+
+    let json = serde_json::to_string(&draft).unwrap();
+    let parsed: Draft = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed, draft);
+    assert_eq!(parsed.minify(false).unwrap(), draft.minify(false).unwrap());
+}
+
+#[test]
+fn draft_classes_are_resolved() {
+    let draft = Draft::from(EXAMPLE).unwrap();
+
+    let json = serde_json::to_value(&draft).unwrap();
+    assert_eq!(json["classes"]["C"]["pattern"], "[ptk]");
+    assert_eq!(json["classes"]["_"]["pattern"], "⟨C⟩ | ⟨V⟩");
+    assert_eq!(
+        json["classes"]["_"]["expanded"],
+        draft.classes["_"].0.as_str()
+    );
+}
+
+#[test]
+fn draft_undefined_class_fails() {
+    let draft = Draft::from(EXAMPLE).unwrap();
+
+    let mut json = serde_json::to_value(&draft).unwrap();
+    json["classes"]
+        .as_object_mut()
+        .unwrap()
+        .remove("V")
+        .unwrap();
+
+    let err = serde_json::from_value::<Draft>(json).unwrap_err();
+    assert!(err.to_string().contains("V"));
+}
+
+#[test]
+fn outcome_serialize_works() {
+    let outcome: Outcome = Draft::from(EXAMPLE).unwrap().run();
+
+    let json = serde_json::to_value(&outcome).unwrap();
+    assert_eq!(json["fail_count"], 0);
+    assert_eq!(json["messages"][2]["Test"]["word"], "kato");
+    assert_eq!(json["messages"][2]["Test"]["status"], "Pass");
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests;
+
+use fancy_regex_macro::regex;
+
+use super::{
+    enumerate::enumerate_pattern, macros::Macros, replace::replace_classes, replace_angle_brackets,
+    segments::segments_pattern, Classes,
+};
+use crate::{
+    error::{Error, Location, ParseError},
+    REGEX_MATCH_FAIL,
+};
+
+/// Operator of set operation between classes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum SetOperator {
+    /// `|` - Segments of either class
+    Union,
+    /// `-` - Segments of first class, but not second class
+    Difference,
+    /// `&` - Segments of both classes
+    Intersection,
+}
+
+/// Get names of classes in set expression, each with the operator before it, if pattern is a set expression
+///
+/// A set expression is class names separated by `|` (union), `-` (difference), or `&` (intersection),
+/// with at least one difference or intersection, such as `<C> - <N>`. Operations are applied from left to right.
+///
+/// Patterns which are not set expressions are regular expressions, including a union of classes only
+pub(super) fn set_expression(pattern: &str) -> Option<Vec<(SetOperator, String)>> {
+    let pattern = replace_angle_brackets(&pattern.replace(' ', ""));
+
+    if !regex!(r"^⟨\w+⟩([-|&]⟨\w+⟩)+$")
+        .is_match(&pattern)
+        .expect(REGEX_MATCH_FAIL)
+        || !pattern.contains(['-', '&'])
+    {
+        return None;
+    }
+
+    let mut expression = Vec::new();
+    let mut operator = SetOperator::Union;
+    for part in pattern.split_inclusive(['-', '|', '&']) {
+        let (name, next) = match part.strip_suffix(['-', '|', '&']) {
+            Some(name) => (name, part.chars().last()),
+            None => (part, None),
+        };
+
+        let name = name.trim_start_matches('⟨').trim_end_matches('⟩');
+        expression.push((operator, name.to_string()));
+
+        operator = match next {
+            Some('-') => SetOperator::Difference,
+            Some('&') => SetOperator::Intersection,
+            _ => SetOperator::Union,
+        };
+    }
+
+    Some(expression)
+}
+
+/// Resolve set expression to a pattern of every segment in the resulting set
+///
+/// Each class is enumerated to a set of segments, so must be finitely enumerable (see `Draft::enumerate_class`)
+///
+/// `classes` must be resolved, and `pattern` is the original pattern, for the span of errors
+pub(super) fn resolve_set(
+    expression: &[(SetOperator, String)],
+    pattern: &str,
+    classes: &Classes,
+    macros: &Macros,
+    location: &Location,
+) -> Result<String, Error> {
+    // Check that every class exists, with span of error
+    replace_classes(pattern, classes, macros, location)?;
+
+    let mut set: Vec<String> = Vec::new();
+
+    for (operator, name) in expression {
+        let (value, _) = &classes[name];
+        let segments = enumerate_pattern(value).map_err(|reason| {
+            Error::Parse(
+                ParseError::SetOperandNotEnumerable(name.clone(), reason.to_string()),
+                location.clone(),
+            )
+        })?;
+
+        match operator {
+            SetOperator::Union => {
+                for segment in segments {
+                    if !set.contains(&segment) {
+                        set.push(segment);
+                    }
+                }
+            }
+            SetOperator::Difference => set.retain(|segment| !segments.contains(segment)),
+            SetOperator::Intersection => set.retain(|segment| segments.contains(segment)),
+        }
+    }
+
+    if set.is_empty() {
+        return Err(Error::Parse(ParseError::EmptyClassSet, location.clone()));
+    }
+
+    Ok(segments_pattern(set, &[]))
+}
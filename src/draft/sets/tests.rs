@@ -0,0 +1,90 @@
+use super::{SetOperator::*, *};
+use crate::Draft;
+
+#[test]
+fn set_expression_works() {
+    assert_eq!(
+        set_expression("<C> - <N>"),
+        Some(vec![
+            (Union, "C".to_string()),
+            (Difference, "N".to_string())
+        ])
+    );
+    assert_eq!(
+        set_expression("⟨A⟩|<B>&<C>-<D>"),
+        Some(vec![
+            (Union, "A".to_string()),
+            (Union, "B".to_string()),
+            (Intersection, "C".to_string()),
+            (Difference, "D".to_string()),
+        ])
+    );
+
+    // Regular expressions
+    assert_eq!(set_expression("<C> | <V>"), None);
+    assert_eq!(set_expression("<C>"), None);
+    assert_eq!(set_expression("[a-z] - <C>"), None);
+    assert_eq!(set_expression("<C>-"), None);
+}
+
+#[test]
+fn resolve_set_works() {
+    let draft = Draft::from(
+        "
+$C = [ptkmnŋ] | ng
+$N = [mnŋ] | ng
+$V = [aeiou]
+$Obstruent = <C> - <N>
+$Nasal = <N> & <C>
+$_ = <Obstruent> | <V> - <Nasal>
++ ^ <Obstruent> $
+?+ p k
+?! m ng a
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        draft.enumerate_class("Obstruent").unwrap(),
+        vec!["k", "p", "t"]
+    );
+    assert_eq!(
+        draft.enumerate_class("Nasal").unwrap(),
+        vec!["ng", "m", "n", "ŋ"]
+    );
+    assert_eq!(
+        draft.enumerate_class("_").unwrap(),
+        vec!["k", "p", "t", "a", "e", "i", "o", "u"]
+    );
+    assert_eq!(draft.run().fail_count, 0);
+}
+
+#[test]
+fn resolve_set_returns_error() {
+    let errors = |file: &str| match Draft::from(file).unwrap_err() {
+        Error::Parse(kind, location) => vec![(kind.to_string(), location.line)],
+        Error::Multiple(errors) => errors
+            .into_iter()
+            .map(|err| match err {
+                Error::Parse(kind, location) => (kind.to_string(), location.line),
+                _ => panic!("Should be parse error"),
+            })
+            .collect(),
+        _ => panic!("Should be parse error"),
+    };
+
+    assert_eq!(
+        errors("$C = [ptk]\n$D = <C> - <X>"),
+        vec![(
+            ParseError::ClassNotFound("X".to_string(), None).to_string(),
+            2
+        )]
+    );
+    assert_eq!(
+        errors("$C = [ptk]\n$D = <C> & <C> - <C>"),
+        vec![(ParseError::EmptyClassSet.to_string(), 2)]
+    );
+    assert!(errors("$C = [ptk]\n$A = .\n$D = <C> - <A>")[0]
+        .0
+        .contains("Class 'A' cannot be used in a set operation"));
+}
@@ -0,0 +1,254 @@
+#[cfg(test)]
+mod tests;
+
+use super::{
+    Draft,
+    Message::{self, *},
+    Note, RawRule, TestDraft,
+};
+use crate::{
+    error::Location,
+    syntax::{FormatOptions, SyntaxTree},
+};
+
+impl Draft {
+    /// Convert draft to readable source, with named classes, notes, rules and tests
+    ///
+    /// The source is formatted with `SyntaxTree::format`, and parses to an equivalent `Draft`.
+    /// This is the inverse of `Draft::minify`, although class names cannot be restored for a minified file
+    ///
+    /// Comments are not included, as they are not kept in a `Draft`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::Draft;
+    /// let draft = Draft::from("$C=[ptk];$V=[aeiou];*:Syllables;+^(<C><V>)+$;?+ kato;?! ak").unwrap();
+    ///
+    /// assert_eq!(
+    ///     draft.to_source(),
+    ///     "~<>\n\n$C = [ptk]\n$V = [aeiou]\n\n*: Syllables\n  + ^(<C><V>)+$\n    ?+ kato\n    ?! ak\n"
+    /// );
+    /// ```
+    pub fn to_source(&self) -> String {
+        let mut lines = Vec::new();
+
+        // Mode and name
+        let mode = self.mode.as_str();
+        lines.push(format!(
+            "~{}{}{}",
+            &mode[..1],
+            self.name.as_deref().unwrap_or_default(),
+            &mode[1..]
+        ));
+
+        if let Some(limit) = self.backtrack_limit {
+            lines.push(format!("@backtrack-limit {}", limit));
+        }
+
+        for name in &self.preludes {
+            lines.push(format!("@prelude {}", name));
+        }
+
+        // Features, in order of definition
+        if !self.features.is_empty() {
+            lines.push(String::new());
+        }
+        for row in &self.features {
+            lines.push(row.to_string());
+        }
+
+        // Mappings of orthography, in order of definition
+        if !self.orthography.is_empty() {
+            lines.push(String::new());
+        }
+        for mapping in &self.orthography {
+            lines.push(mapping.to_string());
+        }
+
+        // Allophones, in order of definition
+        if !self.raw_allophones.is_empty() {
+            lines.push(String::new());
+        }
+        for allophone in &self.raw_allophones {
+            lines.push(allophone.to_string());
+        }
+
+        // Sound changes, in order of definition
+        if !self.raw_changes.is_empty() {
+            lines.push(String::new());
+        }
+        for change in &self.raw_changes {
+            lines.push(change.to_string());
+        }
+
+        // Classes and class macros, in order of definition
+        let mut classes: Vec<_> = self
+            .raw_classes
+            .iter()
+            .map(|(name, (pattern, location))| (name.clone(), pattern, location))
+            .chain(self.macros.iter().map(|(name, class_macro)| {
+                let name = format!("{}({})", name, class_macro.parameters.join(", "));
+                (name, &class_macro.pattern, &class_macro.location)
+            }))
+            .collect();
+        classes.sort_by(|(a, _, a_location), (b, _, b_location)| {
+            let key = |location: &Location| (location.file.clone(), location.offset, location.line);
+            key(a_location).cmp(&key(b_location)).then(a.cmp(b))
+        });
+        if !classes.is_empty() {
+            lines.push(String::new());
+        }
+        for (name, pattern, _) in classes {
+            lines.push(format!("${} = {}", name, pattern));
+        }
+
+        lines.push(String::new());
+        lines.extend(notes_rules_and_tests(&self.raw_rules, &self.messages));
+
+        // Indent, align, and wrap lines
+        SyntaxTree::from(&lines.join("\n")).format(&FormatOptions::default())
+    }
+}
+
+/// Consecutive rules with the same note
+type Block<'a> = (Option<&'a Note>, Vec<&'a RawRule>);
+
+/// Words of consecutive tests with the same intent and expected reason, with transcriptions
+type TestLine<'a> = (bool, Option<&'a Note>, Vec<String>);
+
+/// Get lines of notes, rules and tests, in order
+///
+/// The order of rules, and the order of messages, are kept, and each rule has the same note.
+/// Rules are placed after the displayed note which they use, if any, otherwise a quiet note is used
+fn notes_rules_and_tests(rules: &[RawRule], messages: &[Message<TestDraft>]) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    // Group consecutive rules with the same note
+    let mut blocks: Vec<Block> = Vec::new();
+    for rule in rules {
+        match blocks.last_mut() {
+            Some((note, block)) if *note == rule.note.as_ref() => block.push(rule),
+            _ => blocks.push((rule.note.as_ref(), vec![rule])),
+        }
+    }
+    let mut blocks = blocks.into_iter().peekable();
+
+    // Note which is used for next rule
+    let mut current_note: Option<&Note> = None;
+
+    // Tests on the current line
+    let mut tests: Option<TestLine> = None;
+
+    for (index, msg) in messages.iter().enumerate() {
+        // Rules which do not use a displayed note, before this message
+        // After the first note, these are only placed before notes, to keep tests under their note
+        let is_displayed = |note: Option<&Note>| {
+            messages[index..]
+                .iter()
+                .any(|msg| matches!(msg, Info(info) if Some(info) == note))
+        };
+        let is_placed = msg.is_note() || !messages[..index].iter().any(Message::is_note);
+        if is_placed && blocks.peek().is_some_and(|(note, _)| !is_displayed(*note)) {
+            push_tests(&mut lines, &mut tests);
+            while let Some(block) = blocks.next_if(|(note, _)| !is_displayed(*note)) {
+                push_block(&mut lines, &mut current_note, block);
+            }
+        }
+
+        match msg {
+            Info(note) => {
+                push_tests(&mut lines, &mut tests);
+
+                // Rules which use this note, after any rules before them
+                let remaining: Vec<_> = blocks.clone().map(|(note, _)| note).collect();
+                let index = remaining.iter().position(|block| *block == Some(note));
+
+                if let Some(index) = index {
+                    for _ in 0..index {
+                        let block = blocks.next().expect("Block should exist");
+                        push_block(&mut lines, &mut current_note, block);
+                    }
+                }
+
+                lines.push(String::new());
+                lines.push(format!("* {}", note.0));
+                current_note = Some(note);
+
+                if index.is_some() {
+                    let block = blocks.next().expect("Block should exist");
+                    push_block(&mut lines, &mut current_note, block);
+                }
+            }
+
+            Test(TestDraft {
+                word,
+                intent,
+                reason,
+                transcription,
+                underlying,
+            }) => {
+                let word = match (transcription, underlying) {
+                    (Some(transcription), _) => format!("{} /{}/", word, transcription),
+                    // Narrow transcription is derived again from broad transcription
+                    (_, Some(underlying)) => format!("/{}/", underlying),
+                    _ => word.clone(),
+                };
+
+                // Add to current line, if intent and reason are the same
+                match &mut tests {
+                    Some((last_intent, last_reason, words))
+                        if last_intent == intent && *last_reason == reason.as_ref() =>
+                    {
+                        words.push(word);
+                    }
+                    _ => {
+                        push_tests(&mut lines, &mut tests);
+                        tests = Some((*intent, reason.as_ref(), vec![word]));
+                    }
+                }
+            }
+        }
+    }
+    push_tests(&mut lines, &mut tests);
+
+    // Rules after every message
+    for block in blocks {
+        push_block(&mut lines, &mut current_note, block);
+    }
+
+    lines
+}
+
+/// Add rules of block, with a quiet note if the note is not already used
+///
+/// Rules without a note are given an empty quiet note (`*:`), if a note is used before them
+fn push_block<'a>(lines: &mut Vec<String>, current_note: &mut Option<&'a Note>, block: Block<'a>) {
+    let (note, rules) = block;
+
+    if *current_note != note {
+        lines.push(String::new());
+        match note {
+            Some(Note(text)) => lines.push(format!("*: {}", text)),
+            None => lines.push("*:".to_string()),
+        }
+        *current_note = note;
+    }
+
+    for rule in rules {
+        let operator = if rule.intent { '+' } else { '!' };
+        lines.push(format!("{} {}", operator, rule.pattern));
+    }
+}
+
+/// Add line of tests, if any
+fn push_tests(lines: &mut Vec<String>, tests: &mut Option<TestLine>) {
+    if let Some((intent, reason, words)) = tests.take() {
+        lines.push(format!(
+            "?{}{} {}",
+            if intent { '+' } else { '!' },
+            reason.map_or(String::new(), |Note(reason)| format!("({})", reason)),
+            words.join(" ")
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+use super::*;
+
+/// Assert that source of draft parses to an equivalent draft
+fn assert_equivalent(draft: &Draft) {
+    let source = draft.to_source();
+    let parsed = Draft::from(&source).unwrap();
+
+    assert_eq!(parsed.rules, draft.rules);
+    assert_eq!(parsed.messages, draft.messages);
+    assert_eq!(parsed.mode, draft.mode);
+    assert_eq!(parsed.name, draft.name);
+    assert_eq!(parsed.backtrack_limit, draft.backtrack_limit);
+    assert_eq!(
+        parsed
+            .rules
+            .iter()
+            .map(|rule| &rule.note)
+            .collect::<Vec<_>>(),
+        draft.rules.iter().map(|rule| &rule.note).collect::<Vec<_>>()
+    );
+
+    // Source is already formatted
+    assert_eq!(
+        SyntaxTree::from(&source).format(&FormatOptions::default()),
+        source
+    );
+}
+
+#[test]
+fn to_source_examples_works() {
+    let files = [
+        include_str!("../../../examples/example.phonet"),
+        include_str!("../../../examples/tokipona.phonet"),
+        include_str!("../../../examples/esperanto.phonet"),
+    ];
+
+    for file in files {
+        let draft = Draft::from(file).unwrap();
+        assert_equivalent(&draft);
+
+        // Source of minified draft
+        let minified = Draft::from(&draft.minify(true).unwrap()).unwrap();
+        assert_equivalent(&minified);
+    }
+}
+
+#[test]
+fn to_source_works() {
+    let draft = Draft::from(
+        "
+        ~/Name/
+        @backtrack-limit 100
+        $V = [aeiou]
+        $C = [ptk]
+        ?+ a
+        + ^ <C>
+        ! x
+        * Displayed
+          + a
+          ?+ b c
+          ?!(Displayed) d
+          ?!(Displayed) e
+          ?! f
+        *: Quiet
+          ! <V>{2}
+        * Only note
+          ?+ g
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        draft.to_source(),
+        [
+            "~/Name/",
+            "@backtrack-limit 100",
+            "",
+            "$V = [aeiou]",
+            "$C = [ptk]",
+            "",
+            "+ ^ <C>",
+            "! x",
+            "  ?+ a",
+            "",
+            "* Displayed",
+            "  + a",
+            "    ?+ b c",
+            "    ?!(Displayed) d e",
+            "    ?! f",
+            "",
+            "*: Quiet",
+            "  ! <V>{2}",
+            "",
+            "* Only note",
+            "    ?+ g",
+            "",
+        ]
+        .join("\n")
+    );
+    assert_equivalent(&draft);
+}
+
+#[test]
+fn to_source_features_works() {
+    let draft =
+        Draft::from("$N = <[+nasal]>; @features m n +nasal; @features  p t  -nasal").unwrap();
+
+    assert_eq!(
+        draft.to_source(),
+        [
+            "~<>",
+            "",
+            "@features m n +nasal",
+            "@features p t -nasal",
+            "",
+            "$N = <[+nasal]>",
+            "",
+        ]
+        .join("\n")
+    );
+    assert_equivalent(&draft);
+}
+
+#[test]
+fn to_source_order_works() {
+    // Rules before the note which they use
+    let draft = Draft::from("*:First;+a;* Second;+b;*:First;+c;* First;?+ x").unwrap();
+
+    assert_equivalent(&draft);
+    assert_eq!(
+        draft.to_source(),
+        [
+            "~<>",
+            "",
+            "*: First",
+            "  + a",
+            "",
+            "* Second",
+            "  + b",
+            "",
+            "* First",
+            "  + c",
+            "    ?+ x",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn to_source_rules_without_note_works() {
+    // Rules after an empty quiet note do not have a note
+    let draft = Draft::from("* First;+a;*:;+b;?+ x").unwrap();
+    assert_eq!(draft.rules[0].note, Some(Note("First".to_string())));
+    assert_eq!(draft.rules[1].note, None);
+
+    assert_equivalent(&draft);
+    assert_eq!(
+        draft.to_source(),
+        [
+            "~<>",
+            "",
+            "* First",
+            "  + a",
+            "    ?+ x",
+            "",
+            "*:",
+            "  + b",
+            "",
+        ]
+        .join("\n")
+    );
+
+    // Rules after a skipped note do not have a note
+    let draft = Draft::from("* First;+a;[x] * Second;+b").unwrap();
+    assert_eq!(draft.rules[1].note, None);
+    assert_equivalent(&draft);
+}
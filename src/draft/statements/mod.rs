@@ -1,27 +1,36 @@
 #[cfg(test)]
 mod tests;
 
+use std::ops::Range;
+
 /// Split file into list of statements
-pub(super) fn split_statements(file: &str) -> Vec<(String, usize)> {
+///
+/// Returns text, line number, and character index in file, of each statement
+///
+/// Each character of a statement has the same position relative to the start of the statement, as in the file
+pub(super) fn split_statements(file: &str) -> Vec<(String, usize, usize)> {
     // Vector of statements
-    // Multiline statements are treated as single line, with linebreaks removed
+    // Multiline statements are treated as single line, with linebreaks and `&` replaced with spaces
     let mut statements = vec![];
 
-    // Building single line (and multiline, before '&' character)
+    // Building single line (and multiline, after '&' character)
     let mut build_line = String::new();
-    // Building multiline, optional
-    let mut build_multiline: Option<(String, usize)> = None;
+    // Line number of beginning of multiline, if multiline is active
+    let mut build_multiline: Option<usize> = None;
 
     // Canon line number of statement
     // Multiline uses line number of beginning of statement
     let mut current_line_number = 1;
 
+    // Character index of beginning of current statement
+    let mut statement_start = 0;
+
     // Current statement is a comment
     // Discard before pushing to list
     let mut is_comment = false;
 
     // Loop characters of file
-    for ch in file.chars() {
+    for (index, (byte, ch)) in file.char_indices().enumerate() {
         match ch {
             // Newline or semicolon without multiline
             '\n' | ';' if build_multiline.is_none() => {
@@ -31,14 +40,17 @@ pub(super) fn split_statements(file: &str) -> Vec<(String, usize)> {
                 // If single line is not empty
                 if !build_line.is_empty() {
                     // Push single line to statement
-                    statements.push((build_line, current_line_number));
+                    statements.push((build_line, current_line_number, statement_start));
                     // Reset single line
                     build_line = String::new();
                 }
+
+                // Next statement begins after this character
+                statement_start = index + 1;
             }
 
-            // Newline with multiline - Ignore
-            '\n' => (),
+            // Newline with multiline - Replace with space
+            '\n' => build_line.push(' '),
 
             // Semicolon with multiline
             ';' => {
@@ -47,10 +59,7 @@ pub(super) fn split_statements(file: &str) -> Vec<(String, usize)> {
 
                 // Multiline is active
                 // Unwrap should not fail due to above match guard
-                let (multiline, number) = build_multiline.unwrap();
-
-                // Add multiline to single line, without linebreaks
-                build_line.push_str(&multiline);
+                let number = build_multiline.unwrap();
                 // Reset multiline
                 build_multiline = None;
 
@@ -58,27 +67,34 @@ pub(super) fn split_statements(file: &str) -> Vec<(String, usize)> {
                 // This mirrors the statement in arm of '\n' match, above
                 if !build_line.is_empty() {
                     // Push single line to statement
-                    statements.push((build_line, number));
+                    statements.push((build_line, number, statement_start));
                     // Reset single line
                     build_line = String::new();
                 }
+
+                // Next statement begins after this character
+                statement_start = index + 1;
             }
 
             // Start multiline
-            // Only if not a comment
-            '&' if !is_comment => match &mut build_multiline {
-                // Multiline is not already active
-                None => {
-                    // Start multiline, with current line number
-                    build_multiline = Some((String::new(), current_line_number))
-                }
-
-                // Multiline is already active
-                Some(_) => {
-                    // Add '&' character to single line build
-                    build_line.push(ch);
+            // Only if not a comment, and not an intersection of classes
+            '&' if !is_comment && !is_class_intersection(&build_line, &file[byte + 1..]) => {
+                match build_multiline {
+                    // Multiline is not already active
+                    None => {
+                        // Start multiline, with current line number
+                        build_multiline = Some(current_line_number);
+                        // Replace with space
+                        build_line.push(' ');
+                    }
+
+                    // Multiline is already active
+                    Some(_) => {
+                        // Add '&' character to single line build
+                        build_line.push(ch);
+                    }
                 }
-            },
+            }
 
             // Comment at start of statement
             '#' if build_line.trim().is_empty() => {
@@ -102,20 +118,50 @@ pub(super) fn split_statements(file: &str) -> Vec<(String, usize)> {
     let start_line_number = match build_multiline {
         // Multiline is not active - Use current line number (last line)
         None => current_line_number,
-        // Multiline is active
-        Some((multiline, number)) => {
-            // Add multiline to single line, without linebreaks
-            build_line.push_str(&multiline);
-            // Use line number of beginning of statement
-            number
-        }
+        // Multiline is active - Use line number of beginning of statement
+        Some(number) => number,
     };
 
     // If single line (including multiline) is not empty
     if !build_line.is_empty() {
         // Push single line to statement
-        statements.push((build_line, start_line_number));
+        statements.push((build_line, start_line_number, statement_start));
     }
 
     statements
 }
+
+/// Returns `true` if `&` is an intersection of classes, such as `<A> & <B>`, rather than the start of a multiline statement
+///
+/// `before` is the statement before the `&`, and `after` is the rest of the file after it.
+/// Only class definitions (`$`) can have an intersection, and both class names must be on the same line as the `&`
+pub(crate) fn is_class_intersection(before: &str, after: &str) -> bool {
+    // Skip condition of statement, such as `[strict]`
+    let statement = before.trim_start();
+    let statement = match statement.strip_prefix('[') {
+        Some(rest) => rest
+            .split_once(']')
+            .map_or("", |(_, rest)| rest.trim_start()),
+        None => statement,
+    };
+
+    statement.starts_with('$')
+        && before.trim_end_matches([' ', '\t']).ends_with(['>', '⟩'])
+        && after
+            .trim_start_matches([' ', '\t'])
+            .starts_with(['<', '⟨'])
+}
+
+/// Get range of characters of `part` in `statement`
+///
+/// `part` must be a slice of `statement`
+pub(super) fn span_of(statement: &str, part: &str) -> Range<usize> {
+    // Byte index of start of part
+    let index = (part.as_ptr() as usize)
+        .checked_sub(statement.as_ptr() as usize)
+        .filter(|index| index + part.len() <= statement.len())
+        .unwrap_or_else(|| unrecoverable_error!("Span must be a slice of the statement"));
+
+    let start = statement[..index].chars().count();
+    start..start + part.chars().count()
+}
@@ -2,42 +2,41 @@ use super::*;
 
 #[test]
 fn split_statements_works() {
-    let lhs = split_statements(
-        &[
-            "foo bar & abc 123;",
-            "baz &",
-            "123 456",
-            " abc",
-            ";",
-            "a &",
-            "",
-            "what",
-            "; hello;1; 2;3 & a;4",
-            "pls",
-            "# a comment",
-            "hello & a & b",
-            ";",
-            "# comment no-multiline & ect",
-            "some statement # with a hashtag",
-            "",
-            "# a comment; and a statement",
-        ]
-        .join("\n"),
-    );
+    let file = [
+        "foo bar & abc 123;",
+        "baz &",
+        "123 456",
+        " abc",
+        ";",
+        "a &",
+        "",
+        "what",
+        "; hello;1; 2;3 & a;4",
+        "pls",
+        "# a comment",
+        "hello & a & b",
+        ";",
+        "# comment no-multiline & ect",
+        "some statement # with a hashtag",
+        "",
+        "# a comment; and a statement",
+    ]
+    .join("\n");
+    let lhs = split_statements(&file);
 
     let rhs = vec![
-        ("foo bar  abc 123".to_string(), 1),
-        ("baz 123 456 abc".to_string(), 2),
-        ("a what".to_string(), 6),
-        (" hello".to_string(), 9),
-        ("1".to_string(), 9),
-        (" 2".to_string(), 9),
-        ("3  a".to_string(), 9),
-        ("4".to_string(), 9),
-        ("pls".to_string(), 10),
-        ("hello  a & b".to_string(), 12),
-        ("some statement # with a hashtag".to_string(), 15),
-        (" and a statement".to_string(), 17),
+        ("foo bar   abc 123".to_string(), 1, 0),
+        ("baz   123 456  abc ".to_string(), 2, 19),
+        ("a    what ".to_string(), 6, 40),
+        (" hello".to_string(), 9, 51),
+        ("1".to_string(), 9, 58),
+        (" 2".to_string(), 9, 60),
+        ("3   a".to_string(), 9, 63),
+        ("4".to_string(), 9, 69),
+        ("pls".to_string(), 10, 71),
+        ("hello   a & b ".to_string(), 12, 87),
+        ("some statement # with a hashtag".to_string(), 15, 132),
+        (" and a statement".to_string(), 17, 177),
     ];
 
     // Debugging
@@ -56,5 +55,58 @@ fn split_statements_works() {
     }
     println!("\x1b[36;1m---\x1b[0m");
 
-    // assert_eq!(lhs, rhs);
+    assert_eq!(lhs, rhs);
+}
+
+#[test]
+fn split_statements_keeps_positions() {
+    let file = "~<>;$C = [ptk] # comment\n  ?+ &\n    a b\n    c\n  ;\n# comment\n* note";
+
+    for (statement, _, offset) in split_statements(file) {
+        let original: String = file
+            .chars()
+            .skip(offset)
+            .take(statement.chars().count())
+            .collect();
+
+        // Characters are the same, except multiline characters
+        assert_eq!(statement, original.replace(['\n', '&'], " "));
+    }
+}
+
+#[test]
+fn span_of_works() {
+    let statement = "+ ⟨C⟩ abc";
+
+    assert_eq!(span_of(statement, statement), 0..9);
+    assert_eq!(span_of(statement, &statement[2..]), 2..9);
+    assert_eq!(span_of(statement, statement.trim_end_matches("abc")), 0..6);
+    assert_eq!(span_of(statement, &statement[10..]), 6..9);
+}
+
+#[test]
+fn split_statements_class_intersection_works() {
+    let file = "$A = <B> & <C>\n$D = ⟨E⟩&⟨F⟩\n+ <G> &\n<H>;\n+ a & <B>\n;";
+
+    assert_eq!(
+        split_statements(file),
+        vec![
+            ("$A = <B> & <C>".to_string(), 1, 0),
+            ("$D = ⟨E⟩&⟨F⟩".to_string(), 2, 15),
+            ("+ <G>   <H>".to_string(), 3, 28),
+            ("+ a   <B> ".to_string(), 5, 41),
+        ]
+    );
+
+    assert!(is_class_intersection("$A = <B> ", " <C>"));
+    assert!(!is_class_intersection("$A = <B>", "\n<C>"));
+    assert!(!is_class_intersection("$A = B", "<C>"));
+
+    // Rules are multiline statements
+    assert!(!is_class_intersection("+ <A> ", " <B>"));
+    assert!(is_class_intersection("[strict] $A = <B> ", " <C>"));
+    assert_eq!(
+        split_statements("+ <A> & <B>\n<C>"),
+        vec![("+ <A>   <B> <C>".to_string(), 1, 0)]
+    );
 }
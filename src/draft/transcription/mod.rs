@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests;
+
+use super::Mode;
+use crate::error::ParseError;
+
+/// Opening and closing delimiters of every mode
+const DELIMITERS: &[(char, char)] = &[('<', '>'), ('/', '/'), ('[', ']')];
+
+/// IPA letters and symbols, which are not lowercase ASCII letters, or in the IPA extension or modifier blocks
+const IPA_SYMBOLS: &str = "æçðøħŋœβθχⱱⁿǀǁǂǃ.‿";
+/// Superscript digits, for tone numbers such as `⁵⁵`
+const SUPERSCRIPT_DIGITS: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹";
+
+/// Remove delimiters of the mode from a test word, such as `/kato/` for broad transcription
+///
+/// Delimiters are optional, and are only removed as a matching pair, such as `/kato/` but not `/kato`.
+/// Returns an error if the word has delimiters which do not match the mode,
+/// or if a word in narrow transcription contains a character which is not IPA
+pub(super) fn strip_delimiters(word: &str, mode: Mode) -> Result<String, ParseError> {
+    let mut chars = word.chars();
+    let is_delimited = match (chars.next(), chars.next_back()) {
+        (Some(first), Some(last)) => DELIMITERS.contains(&(first, last)),
+        _ => false,
+    };
+
+    let inner = if is_delimited {
+        let (open, close) = mode.delimiters();
+        match word
+            .strip_prefix(open)
+            .and_then(|inner| inner.strip_suffix(close))
+        {
+            Some("") => return Err(ParseError::InvalidTestWord(word.to_string())),
+            Some(inner) => inner,
+            None => return Err(ParseError::MismatchedTestDelimiters(word.to_string(), mode)),
+        }
+    } else {
+        word
+    };
+
+    if mode == Mode::Narrow {
+        if let Some(ch) = inner.chars().find(|ch| !is_ipa(*ch)) {
+            return Err(ParseError::NonIpaCharacter(word.to_string(), ch));
+        }
+    }
+
+    Ok(inner.to_string())
+}
+
+/// Returns `true` if character is an IPA letter, diacritic, or symbol
+fn is_ipa(ch: char) -> bool {
+    ch.is_ascii_lowercase()
+        || IPA_SYMBOLS.contains(ch)
+        || SUPERSCRIPT_DIGITS.contains(ch)
+        // IPA extensions, spacing modifier letters (such as `ʰ` and `ː`), and combining diacritics (such as tie bars)
+        || matches!(ch, '\u{0250}'..='\u{036F}')
+        // Phonetic extensions (such as `ᵐ` and `ᵑ`), and supplement of combining diacritics
+        || matches!(ch, '\u{1D00}'..='\u{1DFF}')
+}
@@ -0,0 +1,108 @@
+use super::*;
+use crate::{
+    draft::Message,
+    error::{Error, Location},
+    Draft, DraftBuilder,
+};
+
+#[test]
+fn strip_delimiters_works() {
+    use Mode::*;
+
+    assert_eq!(strip_delimiters("kato", Romanized).unwrap(), "kato");
+    assert_eq!(strip_delimiters("<kato>", Romanized).unwrap(), "kato");
+    assert_eq!(strip_delimiters("/kato/", Broad).unwrap(), "kato");
+    assert_eq!(strip_delimiters("kato", Broad).unwrap(), "kato");
+    assert_eq!(strip_delimiters("[kʰato]", Narrow).unwrap(), "kʰato");
+    assert_eq!(strip_delimiters("t͡ʃaŋˈaː", Narrow).unwrap(), "t͡ʃaŋˈaː");
+
+    assert!(matches!(
+        strip_delimiters("/kato/", Narrow),
+        Err(ParseError::MismatchedTestDelimiters(word, Narrow)) if word == "/kato/"
+    ));
+    assert!(matches!(
+        strip_delimiters("<kato>", Broad),
+        Err(ParseError::MismatchedTestDelimiters(_, Broad))
+    ));
+
+    // Only matching pairs are delimiters
+    assert_eq!(strip_delimiters("kato>", Romanized).unwrap(), "kato>");
+    assert_eq!(strip_delimiters("/kato", Broad).unwrap(), "/kato");
+    assert_eq!(strip_delimiters("<kato/", Romanized).unwrap(), "<kato/");
+    assert!(matches!(
+        strip_delimiters("[kato", Narrow),
+        Err(ParseError::NonIpaCharacter(_, '['))
+    ));
+    assert!(matches!(
+        strip_delimiters("//", Broad),
+        Err(ParseError::InvalidTestWord(_))
+    ));
+}
+
+#[test]
+fn narrow_tests_must_be_ipa() {
+    assert!(matches!(
+        strip_delimiters("[Kato]", Mode::Narrow),
+        Err(ParseError::NonIpaCharacter(word, 'K')) if word == "[Kato]"
+    ));
+    assert!(matches!(
+        strip_delimiters("kaĉo", Mode::Narrow),
+        Err(ParseError::NonIpaCharacter(_, 'ĉ'))
+    ));
+
+    // Phonetic extensions and tone numbers
+    assert_eq!(strip_delimiters("[ᵐba]", Mode::Narrow).unwrap(), "ᵐba");
+    assert_eq!(strip_delimiters("ᵑga", Mode::Narrow).unwrap(), "ᵑga");
+    assert_eq!(strip_delimiters("ta⁵⁵", Mode::Narrow).unwrap(), "ta⁵⁵");
+    assert_eq!(strip_delimiters("ma²¹⁴", Mode::Narrow).unwrap(), "ma²¹⁴");
+    assert_eq!(strip_delimiters("ⁿda", Mode::Narrow).unwrap(), "ⁿda");
+
+    let draft = Draft::from("~[]; + ^[ᵐbta⁵]+$; ?+ ᵐba ta⁵⁵").unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+
+    // Other modes can use any characters
+    assert_eq!(strip_delimiters("/Kaĉo/", Mode::Broad).unwrap(), "Kaĉo");
+    assert_eq!(strip_delimiters("Kaĉo", Mode::Romanized).unwrap(), "Kaĉo");
+}
+
+#[test]
+fn delimited_tests_work() {
+    let draft = Draft::from("?+ /kato/ sato; ~//; *: Vowels; ! a{2}; ?!(Vowels) /kaat/").unwrap();
+    let words: Vec<_> = draft
+        .messages
+        .iter()
+        .filter_map(|msg| match msg {
+            Message::Test(test) => Some(test.word.as_str()),
+            Message::Info(_) => None,
+        })
+        .collect();
+    assert_eq!(words, ["kato", "sato", "kaat"]);
+    assert_eq!(draft.run().fail_count, 0);
+
+    let draft = DraftBuilder::new()
+        .mode(Mode::Narrow)
+        .rule(true, "^kʰ")
+        .test(true, "[kʰato]")
+        .build()
+        .unwrap();
+    assert_eq!(draft.run().fail_count, 0);
+}
+
+#[test]
+fn delimited_tests_return_error() {
+    let Err(Error::Multiple(errors)) = Draft::from("~[]\n?+ <kato> [kʰato]\n?! Kato") else {
+        panic!("Should return multiple errors");
+    };
+
+    assert!(matches!(
+        &errors[0],
+        Error::Parse(ParseError::MismatchedTestDelimiters(word, Mode::Narrow), Location { line: 2, span: Some(span), .. })
+            if word == "<kato>" && *span == (3..9)
+    ));
+    assert!(matches!(
+        &errors[1],
+        Error::Parse(ParseError::NonIpaCharacter(_, 'K'), Location { line: 3, span: Some(span), .. })
+            if *span == (3..7)
+    ));
+    assert_eq!(errors.len(), 2);
+}
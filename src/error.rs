@@ -1,24 +1,53 @@
+use std::{fmt, ops::Range, path::PathBuf};
+
+use stilo::stylize;
 use thiserror::Error;
 
+use crate::draft::{Mode, DIRECTIVES, PRELUDES};
+
 /// Error type for *Phonet*
+///
+/// More variants may be added in minor versions
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
-    #[error("Error while parsing: {0}, at line {1}")]
-    Parse(ParseError, usize),
+    #[error("Error while parsing: {0}, at {1}")]
+    Parse(ParseError, Location),
+
+    #[error("{} errors while parsing:\n{}", .0.len(), list_errors(.0))]
+    Multiple(Vec<Error>),
+
+    #[error("Failed to read file '{0}' - {1}")]
+    ReadFile(PathBuf, std::io::Error),
 
     #[error("Missing 'any' class. Use `$_ = ___` to define it")]
     MissingAnyClass,
+
+    #[error("Class not found named '{0}'")]
+    MissingClass(String),
+
+    /// Name of class, and reason that it cannot be enumerated
+    #[error("Class '{0}' cannot be enumerated, as {1}")]
+    ClassNotEnumerable(String, String),
+
+    /// Word, location of rule, and error of regex
+    #[error("Failed to run rule on word '{0}' - {2}, at {1}")]
+    RuleFail(String, Location, Box<fancy_regex::Error>),
 }
 
+/// Kind of error while parsing a file
+///
+/// More variants may be added in minor versions
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum ParseError {
     #[error("Mode already defined")]
     ModeAlreadyDefined,
 
-    #[error("Invalid mode specifier, at line")]
+    #[error("Invalid mode specifier")]
     InvalidModeSpecifier,
 
-    #[error("No class name given, at line")]
+    #[error("No class name given")]
     NoClassName,
 
     #[error("Invalid class name '{0}'")]
@@ -33,20 +62,140 @@ pub enum ParseError {
     #[error("Missing or invalid test intent identifier")]
     InvalidTestIntent,
 
+    #[error("Expected reason of test is not closed")]
+    UnclosedTestReason,
+
+    #[error("Expected reason of test cannot be empty")]
+    EmptyTestReason,
+
+    #[error("Expected reason given for test which should be valid")]
+    ReasonForValidTest,
+
+    #[error("Invalid test word '{0}'")]
+    InvalidTestWord(String),
+
+    /// Test word, and mode of file
+    #[error("Delimiters of test '{0}' do not match mode `~{}`", .1.as_str())]
+    MismatchedTestDelimiters(String, Mode),
+
+    /// Test word, and invalid character
+    #[error("Test '{0}' contains '{1}', which is not an IPA character")]
+    NonIpaCharacter(String, char),
+
+    #[error("Test '{0}' is given a transcription, but the file is not romanized")]
+    TranscriptionNotRomanized(String),
+
+    #[error("Test '{0}' is in broad transcription, but the file has no allophone rules")]
+    NoAllophones(String),
+
+    #[error("Invalid mapping '{0}'")]
+    InvalidMapping(String),
+
+    #[error("Invalid allophone rule '{0}'")]
+    InvalidAllophone(String),
+
+    #[error("Invalid sound change '{0}'")]
+    InvalidSoundChange(String),
+
     #[error("Note cannot be empty")]
     EmptyNote,
 
     #[error("Unknown statement operator '{0}'")]
     UnknownStatementOperator(char),
 
+    #[error("Condition of statement is not closed")]
+    UnclosedCondition,
+
+    #[error("Condition of statement cannot be empty")]
+    EmptyCondition,
+
+    #[error("Invalid tag name '{0}'")]
+    InvalidTag(String),
+
+    #[error("Condition given for statement which cannot be conditional")]
+    ConditionNotAllowed,
+
+    #[error("Unknown directive '@{0}'")]
+    UnknownDirective(String),
+
+    #[error("No file path was given to include")]
+    NoIncludePath,
+
+    #[error(
+        "Cannot include files in a draft parsed from a string. Use `Draft::from_path` instead"
+    )]
+    IncludeWithoutPath,
+
+    #[error("Failed to read included file '{0}' - {1}")]
+    IncludeReadFail(PathBuf, std::io::Error),
+
+    #[error("File '{0}' is included recursively")]
+    CyclicInclude(PathBuf),
+
+    #[error("Backtrack limit already defined")]
+    BacktrackLimitAlreadyDefined,
+
+    #[error("Invalid backtrack limit '{0}'")]
+    InvalidBacktrackLimit(String),
+
+    #[error("Unknown prelude '{0}'")]
+    UnknownPrelude(String),
+
+    #[error("No segments were given for features")]
+    NoFeatureSegments,
+
+    #[error("No features were given for segments")]
+    NoFeatures,
+
+    #[error("Invalid feature '{0}'")]
+    InvalidFeature(String),
+
     #[error("Unexpected class name opening bracket in regex pattern")]
     UnexpectedClassNameOpen,
 
     #[error("Unexpected class name closing bracket in regex pattern")]
     UnexpectedClassNameClose,
 
+    /// Name of class, and name of most similar class which exists (if any)
     #[error("Class not found named '{0}'")]
-    ClassNotFound(String),
+    ClassNotFound(String, Option<String>),
+
+    #[error("Invalid parameter name '{0}'")]
+    InvalidMacroParameter(String),
+
+    #[error("Parameter '{0}' is defined more than once")]
+    DuplicateMacroParameter(String),
+
+    /// Name of class, amount of parameters, and amount of arguments given
+    #[error("Class '{0}' takes {1} arguments, but {2} were given")]
+    ClassArgumentCount(String, usize, usize),
+
+    #[error("Invalid argument '{0}' for class macro")]
+    InvalidClassArgument(String),
+
+    #[error("Class macro '{0}' is used recursively")]
+    CyclicMacro(String),
+
+    /// Name of class, and reason that it cannot be enumerated
+    #[error("Class '{0}' cannot be used in a set operation, as {1}")]
+    SetOperandNotEnumerable(String, String),
+
+    #[error("Set operation of classes results in no segments")]
+    EmptyClassSet,
+
+    #[error("Invalid feature bundle '{0}'")]
+    InvalidFeatureBundle(String),
+
+    /// Name of feature, and name of most similar feature which exists (if any)
+    #[error("Feature not found named '{0}'")]
+    FeatureNotFound(String, Option<String>),
+
+    #[error("Feature bundle '{0}' does not select any segments")]
+    EmptyFeatureBundle(String),
+
+    /// Name and location of each class in the cycle
+    #[error("Class definitions are cyclic: {}", display_cycle(.0))]
+    CyclicClass(Vec<(String, Location)>),
 
     #[error("Unexpected end of regex pattern for class name")]
     UnexpectedPatternEnd,
@@ -54,3 +203,474 @@ pub enum ParseError {
     #[error("Failed to parse rule pattern as regex - {0}")]
     RegexParseFail(fancy_regex::Error),
 }
+
+/// Location of a statement in a source file
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    /// Path of file which the statement was read from
+    ///
+    /// `None` if the draft was parsed directly from a string
+    pub file: Option<PathBuf>,
+    /// Line number of statement
+    ///
+    /// Multiline statements use the line number of the beginning of the statement
+    pub line: usize,
+    /// Index of first character of statement in file
+    pub offset: usize,
+    /// Range of characters in the statement, which the error refers to
+    ///
+    /// `None` if the error refers to the entire statement
+    pub span: Option<Range<usize>>,
+}
+
+impl Error {
+    /// Get list of every error, flattening `Error::Multiple`
+    pub fn list(&self) -> Vec<&Error> {
+        match self {
+            Self::Multiple(errors) => errors.iter().flat_map(Error::list).collect(),
+            _ => vec![self],
+        }
+    }
+
+    /// Display error with a snippet of the source file, with the span of the error underlined
+    ///
+    /// `source` must be the contents of the file which the error occurred in (`Location::file`)
+    ///
+    /// Errors which are not `Error::Parse` are displayed normally
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::Draft;
+    /// let file = "$C = [ptk]\n+ ^<c>+$";
+    ///
+    /// let err = Draft::from(file).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     err.display_snippet(file, false),
+    ///     "\
+    /// error: Class not found named 'c'
+    ///  --> 2:4
+    ///   |
+    /// 2 | + ^<c>+$
+    ///   |    ^^^
+    ///   = help: Did you mean `<C>`?"
+    /// );
+    /// ```
+    pub fn display_snippet(&self, source: &str, do_color: bool) -> String {
+        // Get message, location, and hint of error
+        let (message, location, hint) = match self {
+            Self::Parse(kind, location) => (kind.to_string(), location, kind.hint()),
+
+            Self::RuleFail(word, location, err) => (
+                format!("Failed to run rule on word '{}' - {}", word, err),
+                location,
+                matches!(
+                    **err,
+                    fancy_regex::Error::RuntimeError(
+                        fancy_regex::RuntimeError::BacktrackLimitExceeded
+                    )
+                )
+                .then(|| {
+                    "Simplify the rule, or increase the limit with `@backtrack-limit`".to_string()
+                }),
+            ),
+
+            _ => return self.to_string(),
+        };
+
+        // Range of characters in file
+        let span = location.file_span(source);
+
+        // Get line of start of span
+        let line_start = source
+            .chars()
+            .take(span.start)
+            .collect::<String>()
+            .rfind('\n')
+            .map_or(0, |index| source[..index].chars().count() + 1);
+        let line_number = source
+            .chars()
+            .take(span.start)
+            .filter(|ch| *ch == '\n')
+            .count()
+            + 1;
+        let line: String = source
+            .chars()
+            .skip(line_start)
+            .take_while(|ch| *ch != '\n')
+            .collect();
+        let line = line.trim_end();
+        let column = span.start - line_start;
+
+        // Underline until end of span, or end of line
+        let underline = (span.end.min(line_start + line.chars().count()))
+            .saturating_sub(span.start)
+            .max(1);
+
+        let gutter = " ".repeat(line_number.to_string().len());
+        let file = match &location.file {
+            Some(file) => format!("{}:", file.display()),
+            None => String::new(),
+        };
+
+        let mut output = format!(
+            "{error} {message}\n\
+            {gutter}{arrow} {file}{line_number}:{column}\n\
+            {gutter} {bar}\n\
+            {number} {bar} {line}\n\
+            {gutter} {bar} {space}{underline}",
+            error = stylize!("error:": Red + bold if do_color),
+            arrow = stylize!("-->": Blue if do_color),
+            bar = stylize!("|": Blue if do_color),
+            number = stylize!("{}": Blue if do_color, line_number),
+            column = column + 1,
+            space = " ".repeat(column),
+            underline = stylize!("{}": Red + bold if do_color, "^".repeat(underline)),
+        );
+
+        if let Some(hint) = hint {
+            output += &format!(
+                "\n{gutter} {equals} help: {hint}",
+                equals = stylize!("=": Blue if do_color),
+            );
+        }
+
+        output
+    }
+}
+
+impl ParseError {
+    /// Get a hint to fix the error, if any
+    pub fn hint(&self) -> Option<String> {
+        use ParseError::*;
+
+        Some(match self {
+            InvalidModeSpecifier => {
+                "Use `~<>` for romanized, `~//` for broad, or `~[]` for narrow transcription".to_string()
+            }
+
+            InvalidClassName(_) => {
+                "Class names must only contain letters, numbers, and underscores".to_string()
+            }
+
+            InvalidTestIntent => {
+                "Use `?+` for tests which should be valid, or `?!` for tests which should be invalid"
+                    .to_string()
+            }
+
+            InvalidTestWord(_) => "Test words cannot be empty, or contain whitespace".to_string(),
+
+            MismatchedTestDelimiters(_, mode) => {
+                let (open, close) = mode.delimiters();
+                format!("Write tests as `{}kato{}`, or without delimiters", open, close)
+            }
+
+            NonIpaCharacter(..) => {
+                "Tests in narrow transcription (`~[]`) must only contain IPA characters. Use `~//` or `~<>` for other characters".to_string()
+            }
+
+            TranscriptionNotRomanized(_) => {
+                "Tests can only be given a transcription, such as `?+ kato /kato/`, in romanized mode (`~<>`)".to_string()
+            }
+
+            NoAllophones(_) => {
+                "Define allophone rules to derive narrow tests, such as `@allophone t > tʰ / #_`, or write the test in square brackets".to_string()
+            }
+
+            InvalidAllophone(_) => {
+                "Use a target, a replacement, and an environment (optional), such as `@allophone t > tʰ / #_`".to_string()
+            }
+
+            InvalidSoundChange(_) => {
+                "Use a target, a replacement, and an environment (optional), such as `@change a > e / _i`".to_string()
+            }
+
+            InvalidMapping(_) => {
+                "Use a grapheme and a phoneme, such as `@map ch /t͡ʃ/`".to_string()
+            }
+
+            UnclosedTestReason => "Close the reason with `)`, such as `?!(Some reason) word`".to_string(),
+
+            ReasonForValidTest => {
+                "Only tests which should be invalid (`?!`) can have an expected reason".to_string()
+            }
+
+            UnknownStatementOperator(_) => "Valid operators are `$` (class), `+` or `!` (rule), `?` (test), `*` (note), `~` (mode), `@` (directive), and `#` (comment)".to_string(),
+
+            UnclosedCondition => "Close the condition with `]`, such as `[strict] + ^<C>+$`".to_string(),
+
+            InvalidTag(_) => {
+                "Tag names must only contain letters, numbers, and underscores, and may begin with `!` to negate"
+                    .to_string()
+            }
+
+            ConditionNotAllowed => {
+                "Only classes, rules, tests, and notes can have a condition".to_string()
+            }
+
+            UnknownDirective(name) => match closest_name(name, DIRECTIVES.iter().copied()) {
+                Some(closest) => format!("Did you mean `@{}`?", closest),
+                None => format!(
+                    "Valid directives are {}",
+                    DIRECTIVES
+                        .iter()
+                        .map(|name| format!("`@{}`", name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+
+            InvalidBacktrackLimit(_) => "Backtrack limit must be a positive integer".to_string(),
+
+            UnknownPrelude(name) => match closest_name(name, PRELUDES.iter().map(|(name, _)| *name)) {
+                Some(closest) => format!("Did you mean `@prelude {}`?", closest),
+                None => format!(
+                    "Valid preludes are {}",
+                    PRELUDES
+                        .iter()
+                        .map(|(name, _)| format!("`{}`", name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+
+            NoFeatureSegments => {
+                "Segments are given before features, such as `@features p t k -voice`".to_string()
+            }
+
+            InvalidFeature(_) => {
+                "Features begin with `+` or `-`, followed by a name of letters, numbers, and underscores, such as `+voice`"
+                    .to_string()
+            }
+
+            InvalidFeatureBundle(_) => {
+                "Feature bundles are features separated by spaces, in brackets, such as `<[+voice -nasal]>`"
+                    .to_string()
+            }
+
+            FeatureNotFound(_, Some(closest)) => format!("Did you mean `{}`?", closest),
+
+            InvalidMacroParameter(_) => {
+                "Parameter names must only contain letters, numbers, and underscores, such as `$Geminate(x) = <x><x>`"
+                    .to_string()
+            }
+
+            ClassArgumentCount(name, 0, _) => {
+                format!("Class `{}` has no parameters, so use `<{}>`", name, name)
+            }
+
+            ClassArgumentCount(name, count, _) => format!(
+                "Give {} class names as arguments, such as `<{}({})>`",
+                count,
+                name,
+                vec!["C"; *count].join(", ")
+            ),
+
+            InvalidClassArgument(_) => {
+                "Arguments must be class names, separated by commas, such as `<Geminate(C)>`".to_string()
+            }
+
+            SetOperandNotEnumerable(..) => {
+                "Classes in set operations must match a finite set of segments, such as `[ptk]` or `{p t k ng}`"
+                    .to_string()
+            }
+
+            ClassNotFound(_, Some(closest)) => format!("Did you mean `<{}>`?", closest),
+
+            UnexpectedPatternEnd => "Close class name with `>` or `⟩`".to_string(),
+
+            _ => return None,
+        })
+    }
+}
+
+impl Location {
+    /// Returns a clone of the location, with a span of characters in the statement
+    pub(crate) fn with_span(&self, span: Range<usize>) -> Self {
+        Self {
+            span: Some(span),
+            ..self.clone()
+        }
+    }
+
+    /// Get range of characters in the file, which the location refers to
+    ///
+    /// If location has no span, the trimmed statement on the first line is used
+    pub fn file_span(&self, source: &str) -> Range<usize> {
+        match &self.span {
+            Some(span) => self.offset + span.start..self.offset + span.end,
+
+            None => {
+                let line: String = source
+                    .chars()
+                    .skip(self.offset)
+                    .take_while(|ch| !matches!(ch, '\n' | ';'))
+                    .collect();
+                let start = self.offset + line.chars().take_while(|ch| ch.is_whitespace()).count();
+                start..self.offset + line.trim_end().chars().count()
+            }
+        }
+    }
+
+    /// Returns a clone of the location, with a span relative to the start of the current span
+    pub(crate) fn sub_span(&self, span: Range<usize>) -> Self {
+        let start = self.span.as_ref().map_or(0, |span| span.start);
+        self.with_span(start + span.start..start + span.end)
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}", self.line)?;
+
+        if let Some(file) = &self.file {
+            write!(f, " of '{}'", file.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Display names and line numbers of classes in a cycle
+fn display_cycle(cycle: &[(String, Location)]) -> String {
+    let mut names: Vec<_> = cycle
+        .iter()
+        .map(|(name, location)| format!("<{}> ({})", name, location))
+        .collect();
+
+    // Return to start of cycle
+    if let Some((first, _)) = cycle.first() {
+        names.push(format!("<{}>", first));
+    }
+
+    names.join(" -> ")
+}
+
+/// Display list of errors, each on a new line
+fn list_errors(errors: &[Error]) -> String {
+    errors
+        .iter()
+        .map(|err| format!("  - {}", err))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Get the most similar name to `name` from a list, if any is similar enough
+///
+/// Uses the Levenshtein distance of names, ignoring case
+pub(crate) fn closest_name<'a>(
+    name: &str,
+    names: impl Iterator<Item = &'a str>,
+) -> Option<&'a str> {
+    // Maximum distance to be considered similar
+    let max = name.chars().count().max(2) / 2;
+
+    names
+        .map(|other| {
+            (
+                other,
+                edit_distance(&name.to_lowercase(), &other.to_lowercase()),
+            )
+        })
+        .filter(|(_, distance)| *distance <= max)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(other, _)| other)
+}
+
+/// Get the Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+
+    // Distances of previous row
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_name_works() {
+        let names = ["C", "V", "Vowel", "_"];
+
+        assert_eq!(closest_name("c", names.into_iter()), Some("C"));
+        assert_eq!(closest_name("Vowels", names.into_iter()), Some("Vowel"));
+        assert_eq!(closest_name("Xyz", names.into_iter()), None);
+    }
+
+    #[test]
+    fn edit_distance_works() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("include", "inclde"), 1);
+    }
+
+    #[test]
+    fn file_span_works() {
+        let source = "~<>\n  + ^ <X> $ ;# comment";
+
+        let location = Location {
+            line: 2,
+            offset: 4,
+            ..Default::default()
+        };
+        assert_eq!(location.file_span(source), 6..15);
+        assert_eq!(location.with_span(6..9).file_span(source), 10..13);
+    }
+
+    #[test]
+    fn display_snippet_works() {
+        let source = "~<>\n$C = [ptk]\n  ?+ a &\n    b ; ?x";
+        let location = |line, offset, span| Location {
+            line,
+            offset,
+            span: Some(span),
+            file: Some(PathBuf::from("file.phonet")),
+        };
+
+        // Multiline statement
+        let err = Error::Parse(
+            ParseError::ClassNotFound("b".into(), None),
+            location(3, 15, 13..14),
+        );
+        assert_eq!(
+            err.display_snippet(source, false),
+            "\
+error: Class not found named 'b'
+ --> file.phonet:4:5
+  |
+4 |     b ; ?x
+  |     ^"
+        );
+
+        // Hint
+        let err = Error::Parse(ParseError::InvalidTestIntent, location(4, 31, 2..3));
+        assert_eq!(
+            err.display_snippet(source, false),
+            "\
+error: Missing or invalid test intent identifier
+ --> file.phonet:4:10
+  |
+4 |     b ; ?x
+  |          ^
+  = help: Use `?+` for tests which should be valid, or `?!` for tests which should be invalid"
+        );
+    }
+}
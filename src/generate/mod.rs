@@ -3,35 +3,36 @@ mod tests;
 
 use std::ops::RangeInclusive;
 
-use fancy_regex_macro::regex;
 use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
 
 use crate::{
-    draft::{replace_classes, Classes, Draft, Rule},
+    draft::{Draft, Rule},
     error::Error,
-    outcome::{validate_test, Validity::*},
+    outcome::{validate_test, EvalMode, Validity::*},
 };
 
 /// Generator for random valid words
 pub struct Generator {
     rng: ThreadRng,
-    /// Word length range
+    /// Word length range, in segments
     length: RangeInclusive<usize>,
-    /// Letters from 'any' class
-    letters: String,
+    /// Segments from 'any' class
+    segments: Vec<String>,
     /// Rules to test against
     rules: Vec<Rule>,
 }
 
 impl Generator {
     /// Create a new word `Generator` from a `Draft`, with a word length range
+    ///
+    /// Length is the amount of segments, so a segment with multiple characters (such as `ng`) counts as one
     pub fn new(draft: &Draft, length: RangeInclusive<usize>) -> Result<Self, Error> {
-        let letters = get_letters(&draft.raw_classes)?;
+        let segments = get_segments(draft)?;
 
         Ok(Self {
             rng: rand::thread_rng(),
             length,
-            letters,
+            segments,
             rules: draft.rules.clone(),
         })
     }
@@ -43,10 +44,14 @@ impl Generator {
             let length = self.rng.gen_range(self.length.clone());
 
             // Generate possibly invalid word
-            let word = random_word(&self.letters, length, &mut self.rng);
+            let word = random_word(&self.segments, length, &mut self.rng);
 
             // Check if it is valid
-            if matches!(validate_test(&word, &self.rules), Valid) {
+            // Words which a rule fails to run on are treated as invalid
+            if matches!(
+                validate_test(&word, &self.rules, EvalMode::FirstViolation),
+                Ok(Valid)
+            ) {
                 return word;
             }
         }
@@ -60,39 +65,21 @@ impl Draft {
     }
 }
 
-/// Generate a random word, with a set length, that may not be valid
-fn random_word(letters: &str, length: usize, rng: &mut ThreadRng) -> String {
-    let chars: Vec<char> = letters.chars().collect();
+/// Generate a random word, with a set amount of segments, that may not be valid
+fn random_word(segments: &[String], length: usize, rng: &mut ThreadRng) -> String {
     let mut word = String::new();
 
     for _ in 0..length {
-        word.push(*chars.choose(rng).unwrap());
+        word.push_str(segments.choose(rng).unwrap());
     }
 
     word
 }
 
-/// Get letters of 'any' class, without regex symbols
-fn get_letters(classes: &Classes) -> Result<String, Error> {
-    Ok(remove_regex_symbols(&get_any_class(classes)?))
-}
-
-/// Remove most regex symbols from a string, such as `[`, `]`, `|`, ect
-fn remove_regex_symbols(pattern: &str) -> String {
-    regex!(r"[\[\]|?*+{}():]")
-        .replace_all(pattern, "")
-        .to_string()
-}
-
-/// Get 'any' class (named `_`) from classes
-fn get_any_class(classes: &Classes) -> Result<String, Error> {
-    // Get class
-    let Some((pattern, line)) = classes.get("_") else {
-        return Err(Error::MissingAnyClass);
-    };
-
-    // Replace inner classes
-    let pattern = replace_classes(pattern, classes, *line)?;
-
-    Ok(pattern)
+/// Get segments of 'any' class (named `_`)
+fn get_segments(draft: &Draft) -> Result<Vec<String>, Error> {
+    match draft.enumerate_class("_") {
+        Err(Error::MissingClass(_)) => Err(Error::MissingAnyClass),
+        result => result,
+    }
 }
@@ -5,14 +5,62 @@ use crate::draft::Note;
 use super::*;
 
 #[test]
-fn get_letters_works() {
-    let classes = example_classes!();
+fn get_segments_works() {
+    let draft = Draft::from("$C = {p ng ch}; $_ = <C>|[a-c]").unwrap();
+    assert_eq!(
+        get_segments(&draft).unwrap(),
+        vec!["ng", "ch", "p", "a", "b", "c"]
+    );
+
+    assert!(matches!(
+        get_segments(&Draft::from("$C = [ptk]").unwrap()),
+        Err(Error::MissingAnyClass)
+    ));
+    assert!(matches!(
+        get_segments(&Draft::from("$_ = [ptk]+").unwrap()),
+        Err(Error::ClassNotEnumerable(name, _)) if name == "_"
+    ));
+}
+
+#[test]
+fn examples_have_segments() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
 
-    assert_eq!(get_any_class(&classes).unwrap(), "[[ptk][aeiou]]");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        // Minified files do not keep classes
+        let name = path.file_name().unwrap().to_string_lossy();
+        if !name.ends_with(".phonet") || name.ends_with(".min.phonet") {
+            continue;
+        }
+
+        let draft = Draft::from_path(&path).unwrap();
+        let segments = get_segments(&draft)
+            .unwrap_or_else(|err| panic!("{} has no segments: {}", path.display(), err));
+        assert!(
+            segments
+                .iter()
+                .all(|segment| segment.chars().all(char::is_alphabetic)),
+            "{} has invalid segments: {:?}",
+            path.display(),
+            segments
+        );
+    }
+}
+
+#[test]
+fn generate_segments_works() {
+    let draft = Draft::from("$_ = {ng ch a}; ! ^ng").unwrap();
+    let mut words = draft.generator(3..=3).unwrap();
 
-    assert_eq!(remove_regex_symbols("[[ptk][aeiou]]"), "ptkaeiou");
+    for _ in 0..20 {
+        let word = words.next();
 
-    assert_eq!(get_letters(&classes).unwrap(), "ptkaeiou");
+        // Length is amount of segments
+        let length = word.len() - word.matches("ng").count() - word.matches("ch").count();
+        assert_eq!(length, 3);
+        assert!(!word.starts_with("ng"));
+    }
 }
 
 #[test]
@@ -22,13 +70,17 @@ fn generate_works() {
     let rules = vec![
         Rule {
             pattern: Regex::new("^[[ptk][aeiou]]+$").unwrap(),
+            source: "^[[ptk][aeiou]]+$".to_string(),
             intent: true,
             note: Some(Note("Should contain ⟨a⟩".to_string())),
+            location: Default::default(),
         },
         Rule {
             pattern: Regex::new("[aeiou][aeiou]").unwrap(),
+            source: "[aeiou][aeiou]".to_string(),
             intent: false,
             note: None,
+            location: Default::default(),
         },
     ];
 
@@ -40,17 +92,42 @@ fn generate_works() {
         mode: Default::default(),
         name: None,
         test_count: 0,
+        backtrack_limit: None,
         raw_rules: vec![],
-        raw_classes: classes,
+        raw_classes: Default::default(),
+        macros: Default::default(),
+        preludes: vec![],
+        features: vec![],
+        orthography: vec![],
+        raw_allophones: vec![],
+        allophones: vec![],
+        raw_changes: vec![],
+        changes: vec![],
+        classes,
     };
 
     // Generate some random valid words
     let mut words = draft.generator(length).unwrap();
 
     // Check some words are valid
-    assert!(matches!(validate_test(&words.next(), &rules), Valid));
-    assert!(matches!(validate_test(&words.next(), &rules), Valid));
-    assert!(matches!(validate_test(&words.next(), &rules), Valid));
-    assert!(matches!(validate_test(&words.next(), &rules), Valid));
-    assert!(matches!(validate_test(&words.next(), &rules), Valid));
+    assert!(matches!(
+        validate_test(&words.next(), &rules, EvalMode::FirstViolation),
+        Ok(Valid)
+    ));
+    assert!(matches!(
+        validate_test(&words.next(), &rules, EvalMode::FirstViolation),
+        Ok(Valid)
+    ));
+    assert!(matches!(
+        validate_test(&words.next(), &rules, EvalMode::FirstViolation),
+        Ok(Valid)
+    ));
+    assert!(matches!(
+        validate_test(&words.next(), &rules, EvalMode::FirstViolation),
+        Ok(Valid)
+    ));
+    assert!(matches!(
+        validate_test(&words.next(), &rules, EvalMode::FirstViolation),
+        Ok(Valid)
+    ));
 }
@@ -1,12 +1,15 @@
-/// Example of `Classes` hashmap FOR TESTS
+/// Example of resolved `Classes` hashmap FOR TESTS
 #[cfg(test)]
 macro_rules! example_classes {
     () => {{
         let mut hm = crate::draft::Classes::new();
 
-        hm.insert("C".to_string(), ("[ptk]".to_string(), 0));
-        hm.insert("V".to_string(), ("[aeiou]".to_string(), 0));
-        hm.insert("_".to_string(), ("[⟨C⟩⟨V⟩]".to_string(), 0));
+        hm.insert("C".to_string(), ("[ptk]".to_string(), Default::default()));
+        hm.insert("V".to_string(), ("[aeiou]".to_string(), Default::default()));
+        hm.insert(
+            "_".to_string(),
+            ("[[ptk][aeiou]]".to_string(), Default::default()),
+        );
 
         hm
     }};
@@ -49,8 +52,10 @@ pub mod draft;
 pub mod error;
 /// Running and displaying of *Phonet* `Draft`
 pub mod outcome;
+/// Lossless syntax tree of *Phonet* file, for formatting
+pub mod syntax;
 
-/// Color styles for stdout
+// Color styles for stdout
 // mod color;
 
 /// Generation of random words
@@ -58,7 +63,7 @@ mod generate;
 
 pub use crate::{
     // color::colorize,
-    draft::Draft,
+    draft::{Draft, DraftBuilder},
     outcome::{DisplayLevel, Outcome},
 };
 
@@ -1,11 +1,13 @@
 #[cfg(test)]
 mod tests;
 
+use std::ops::Range;
+
 use stilo::{style, stylize, writeln_styles};
 
 use super::Outcome;
 use crate::{
-    outcome::{FailKind::*, Message::*, Note, PassStatus::*, TestOutcome},
+    outcome::{FailKind::*, Message::*, Note, PassStatus::*, TestOutcome, Violation},
     DisplayLevel::{self, *},
 };
 
@@ -17,12 +19,12 @@ impl Outcome {
         self.messages
             .iter()
             .map(|msg| match msg {
-                // Test - Check display level
-                Test(TestOutcome { word, status, .. }) => match display_level {
+                // Test - Check display level, including underlying form
+                Test(test) => match display_level {
                     // Always include
-                    ShowAll => word.chars().count(),
+                    ShowAll => display_len(test),
                     // Only include if failed
-                    IgnorePasses | OnlyFails if status.is_fail() => word.chars().count(),
+                    IgnorePasses | OnlyFails if test.status.is_fail() => display_len(test),
                     // Don't include
                     _ => 0,
                 },
@@ -54,6 +56,37 @@ impl Outcome {
         out: &mut dyn std::io::Write,
         display_level: DisplayLevel,
         do_color: bool,
+    ) -> Result<(), std::io::Error> {
+        self.write_results(out, display_level, false, do_color)
+    }
+
+    /// Display results to standard output, listing every violated rule under each failing test
+    ///
+    /// Use with an outcome ran with `EvalMode::AllViolations`, otherwise only the first violated rule is listed
+    pub fn display_all_reasons(&self, display_level: DisplayLevel, do_color: bool) {
+        self.display_all_reasons_with(&mut std::io::stdout(), display_level, do_color)
+            .expect("Could not write to stdout");
+    }
+
+    /// Display results, listing every violated rule under each failing test, using custom output
+    pub fn display_all_reasons_with(
+        &self,
+        out: &mut dyn std::io::Write,
+        display_level: DisplayLevel,
+        do_color: bool,
+    ) -> Result<(), std::io::Error> {
+        self.write_results(out, display_level, true, do_color)
+    }
+
+    /// Write results to output
+    ///
+    /// If `all_reasons` is true, every violated rule is listed under each failing test
+    fn write_results(
+        &self,
+        out: &mut dyn std::io::Write,
+        display_level: DisplayLevel,
+        all_reasons: bool,
+        do_color: bool,
     ) -> Result<(), std::io::Error> {
         let test_count = self.test_count();
 
@@ -89,11 +122,15 @@ impl Outcome {
                 },
 
                 // Display test
-                Test(TestOutcome {
-                    word,
-                    intent,
-                    status,
-                }) => {
+                Test(
+                    test @ TestOutcome {
+                        word,
+                        intent,
+                        status,
+                        violations,
+                        ..
+                    },
+                ) => {
                     // Skip if not required by display level
                     match display_level {
                         // Always show
@@ -110,14 +147,47 @@ impl Outcome {
                         Fail(ShouldBeInvalid) => {
                             stylize!("Valid, but should be invalid": Yellow if do_color)
                         }
-                        Fail(NoReasonGiven) => stylize!("No reason given": + italic),
+                        Fail(NoReasonGiven) => stylize!("No reason given": + italic if do_color),
                         Fail(CustomReason(Note(reason))) => String::from(reason),
+                        Fail(WrongReason(Note(expected), actual)) => format!(
+                            "{} {}",
+                            stylize!("Expected '{}', but was": Yellow if do_color, expected),
+                            match actual {
+                                Some(Note(actual)) => format!("'{}'", actual),
+                                None => stylize!("No reason given": + italic if do_color),
+                            }
+                        ),
+                        Fail(RuleError(location, err)) => {
+                            stylize!("Rule failed at {} - {}": Red if do_color, location, err)
+                        }
+                        Fail(WrongTranscription(expected, actual)) => stylize!(
+                            "Expected /{}/, but was /{}/": Yellow if do_color,
+                            expected, actual
+                        ),
+                    };
+
+                    // Spans of word which caused test to fail, from first violated rule
+                    let spans = match (status, violations.first()) {
+                        (
+                            Fail(NoReasonGiven | CustomReason(_) | WrongReason(..)),
+                            Some(violation),
+                        ) => violation.spans.as_slice(),
+                        _ => &[],
                     };
 
+                    // Underlying form of word, if derived
+                    let prefix = test.underlying_prefix();
+
                     // Display test outcome
                     writeln!(
                         out,
-                        "  {intent} {word}{space}  {status} {reason}",
+                        "  {intent} {prefix}{word}{space}  {status} {reason}",
+                        // Word, with spans highlighted
+                        word = if do_color {
+                            highlight_spans(word, spans)
+                        } else {
+                            word.to_string()
+                        },
                         // Intent
                         intent = if *intent {
                             stylize!("✔": Cyan if do_color)
@@ -125,7 +195,7 @@ impl Outcome {
                             stylize!("✘": Magenta if do_color)
                         },
                         // Spacing after word
-                        space = " ".repeat(max_word_len - word.chars().count()),
+                        space = " ".repeat(max_word_len - display_len(test)),
                         // Status of test
                         status = if status.is_pass() {
                             stylize!(
@@ -144,6 +214,43 @@ impl Outcome {
                             stylize!("FAIL": Red + bold if do_color)
                         },
                     )?;
+
+                    // Underline spans on next line, if no color is used
+                    if !do_color && !spans.is_empty() {
+                        let offset = " ".repeat(prefix.chars().count());
+                        writeln!(out, "    {}{}", offset, underline_spans(spans))?;
+                    }
+
+                    // List every violated rule, if test failed for being invalid
+                    if all_reasons
+                        && matches!(
+                            status,
+                            Fail(NoReasonGiven | CustomReason(_) | WrongReason(..))
+                        )
+                    {
+                        for Violation {
+                            note,
+                            intent,
+                            location,
+                            ..
+                        } in violations
+                        {
+                            writeln!(
+                                out,
+                                "      {bullet} {reason} {detail}",
+                                bullet = stylize!("-": Red if do_color),
+                                reason = match note {
+                                    Some(Note(note)) => note.to_string(),
+                                    None => stylize!("No reason given": + italic if do_color),
+                                },
+                                detail = stylize!(
+                                    "(should {}match, at {})": + dim if do_color,
+                                    if *intent { "" } else { "not " },
+                                    location,
+                                ),
+                            )?;
+                        }
+                    }
                 }
             }
         }
@@ -161,6 +268,40 @@ impl Outcome {
     }
 }
 
+/// Get length of word as displayed, including underlying form
+fn display_len(test: &TestOutcome) -> usize {
+    test.underlying_prefix().chars().count() + test.word.chars().count()
+}
+
+/// Style characters of word which are in any span
+fn highlight_spans(word: &str, spans: &[Range<usize>]) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(index, ch)| {
+            if spans.iter().any(|span| span.contains(&index)) {
+                stylize!("{}": Red + underline, ch)
+            } else {
+                ch.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Get line of carets under every span, for output without color
+fn underline_spans(spans: &[Range<usize>]) -> String {
+    let length = spans.iter().map(|span| span.end).max().unwrap_or(0);
+
+    (0..length)
+        .map(|index| {
+            if spans.iter().any(|span| span.contains(&index)) {
+                '^'
+            } else {
+                ' '
+            }
+        })
+        .collect()
+}
+
 /// Returns `"s"` if number does not equal `1`, else a blank string
 fn pluralize(number: usize) -> &'static str {
     if number == 1 {
@@ -4,6 +4,7 @@ use fancy_regex::Regex;
 
 use crate::{
     draft::{Message::*, Note, Rule, TestDraft},
+    outcome::EvalMode,
     DisplayLevel, Draft,
 };
 
@@ -12,8 +13,10 @@ fn max_word_len_works() {
     let outcome = Draft {
         rules: vec![Rule {
             pattern: Regex::new("a").unwrap(),
+            source: "a".to_string(),
             intent: false,
             note: Some(Note("Should not contain ⟨a⟩".to_string())),
+            location: Default::default(),
         }],
         messages: vec![
             Info(Note("This is a really really long note".to_string())),
@@ -21,18 +24,34 @@ fn max_word_len_works() {
             Test(TestDraft {
                 word: "hello".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
             // Failing test
             Test(TestDraft {
                 word: "abc".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
         ],
         mode: Default::default(),
         name: None,
         test_count: 2,
+        backtrack_limit: None,
         //
         raw_classes: HashMap::new(),
+        macros: Default::default(),
+        preludes: vec![],
+        features: vec![],
+        orthography: vec![],
+        raw_allophones: vec![],
+        allophones: vec![],
+        raw_changes: vec![],
+        changes: vec![],
+        classes: HashMap::new(),
         raw_rules: vec![],
     }
     .run();
@@ -42,3 +61,79 @@ fn max_word_len_works() {
     assert_eq!(outcome.max_word_len(DisplayLevel::OnlyFails), 3); // abc
     assert_eq!(outcome.max_word_len(DisplayLevel::HideAll), 0); // [none]
 }
+
+#[test]
+fn display_all_reasons_works() {
+    let draft = Draft::from("*Should contain ⟨a⟩\n+ a\n!x\n?+ box").unwrap();
+
+    // Only first reason displayed
+    let mut out = Vec::new();
+    draft
+        .run_with(EvalMode::AllViolations)
+        .display_with(&mut out, DisplayLevel::ShowAll, false)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "Running 1 test...\nShould contain ⟨a⟩\n  ✔ box  FAIL Should contain ⟨a⟩\n1 test failed\n"
+    );
+
+    // Every reason listed under failing test
+    let mut out = Vec::new();
+    draft
+        .run_with(EvalMode::AllViolations)
+        .display_all_reasons_with(&mut out, DisplayLevel::ShowAll, false)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "Running 1 test...\n\
+        Should contain ⟨a⟩\n  \
+        ✔ box  FAIL Should contain ⟨a⟩\n      \
+        - Should contain ⟨a⟩ (should match, at line 2)\n      \
+        - Should contain ⟨a⟩ (should not match, at line 3)\n\
+        1 test failed\n"
+    );
+}
+
+#[test]
+fn display_spans_works() {
+    let draft = Draft::from("! (.)\\1\n+ ^[ptkaeiou]+$\n?+ taatta\n?+ taxxo").unwrap();
+
+    let mut out = Vec::new();
+    draft
+        .run()
+        .display_with(&mut out, DisplayLevel::ShowAll, false)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        [
+            "Running 2 tests...",
+            "  ✔ taatta  FAIL No reason given",
+            "     ^^^^",
+            "  ✔ taxxo   FAIL No reason given",
+            "      ^^",
+            "2 tests failed\n",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn display_wrong_reason_works() {
+    let draft = Draft::from("*Invalid letters\n+ ^[ptkaeiou]+$\n?!(Vowel clusters) taxa").unwrap();
+
+    let mut out = Vec::new();
+    draft
+        .run()
+        .display_with(&mut out, DisplayLevel::OnlyFails, false)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        [
+            "Running 1 test...",
+            "  ✘ taxa  FAIL Expected 'Vowel clusters', but was 'Invalid letters'",
+            "      ^",
+            "1 test failed\n",
+        ]
+        .join("\n")
+    );
+}
@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod tests;
+
+use stilo::{stylize, writeln_styles};
+
+use super::{
+    run::{validate_test, violation_spans, EvalMode, Validity},
+    Violation,
+};
+use crate::{
+    draft::{Draft, Note},
+    error::{Error, Location},
+};
+
+/// Words evolved with the sound changes of a draft, in order
+///
+/// Use `Draft::evolve` to create, and `Evolution::validate` to check evolved words against the rules of a daughter language
+///
+/// # Examples
+///
+/// ```
+/// # use phonet::Draft;
+/// let changes = Draft::from("
+///   $C = [ptk]
+///   @change a > e / _<C>i
+///   @change k > t͡ʃ / _i
+/// ").unwrap();
+///
+/// let daughter = Draft::from("
+///   * Vowel clusters
+///     ! [aeiou]{2}
+/// ").unwrap();
+///
+/// let mut evolution = changes.evolve(&["taki", "kaito"], true).unwrap();
+/// evolution.validate(&daughter).unwrap();
+///
+/// assert_eq!(evolution.words[0].result, "tet͡ʃi");
+/// assert_eq!(evolution.words[0].history.len(), 2);
+/// assert_eq!(evolution.invalid_count(), 1);
+///
+/// evolution.display(true); // Prints every word to stdout
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Evolution {
+    /// Every word, in the order given
+    pub words: Vec<EvolvedWord>,
+    /// Whether evolved words were checked against a daughter draft, with `Evolution::validate`
+    pub is_validated: bool,
+}
+
+/// Single word, evolved with every sound change
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EvolvedWord {
+    /// Original word
+    pub word: String,
+    /// Word after every sound change
+    pub result: String,
+    /// Every sound change which altered the word, in order
+    ///
+    /// Empty, unless history was kept
+    pub history: Vec<ChangeStep>,
+    /// First rule of the daughter draft which the evolved word violates (optional)
+    ///
+    /// Only given once validated with `Evolution::validate`
+    pub violation: Option<Violation>,
+}
+
+/// Sound change which altered a word
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangeStep {
+    /// Sound change as written in file
+    pub rule: String,
+    /// Location of sound change in file
+    pub location: Location,
+    /// Word after the change
+    pub word: String,
+}
+
+impl Evolution {
+    /// Check every evolved word against the rules of a daughter draft
+    ///
+    /// Returns an error if any rule fails to run on a word (such as exceeding the backtrack limit)
+    pub fn validate(&mut self, daughter: &Draft) -> Result<(), Error> {
+        for word in &mut self.words {
+            word.violation =
+                match validate_test(&word.result, &daughter.rules, EvalMode::default())? {
+                    Validity::Valid => None,
+                    Validity::Invalid(violations) => {
+                        violations.into_iter().next().map(|violation| Violation {
+                            spans: violation_spans(&word.result, &daughter.rules[violation.rule]),
+                            ..violation
+                        })
+                    }
+                };
+        }
+
+        self.is_validated = true;
+        Ok(())
+    }
+
+    /// Get amount of evolved words which violate a rule of the daughter draft
+    pub fn invalid_count(&self) -> usize {
+        self.words
+            .iter()
+            .filter(|word| word.violation.is_some())
+            .count()
+    }
+
+    /// Display evolution to standard output
+    pub fn display(&self, do_color: bool) {
+        self.display_with(&mut std::io::stdout(), do_color)
+            .expect("Could not write to stdout");
+    }
+
+    /// Display evolution, using custom output
+    pub fn display_with(
+        &self,
+        out: &mut dyn std::io::Write,
+        do_color: bool,
+    ) -> Result<(), std::io::Error> {
+        let count = self.words.len();
+        writeln_styles!(
+            out,
+            "Evolving {} word{}...": Yellow if do_color,
+            count, if count == 1 { "" } else { "s" }
+        )?;
+
+        // Length of longest word and evolved word, for alignment
+        let max_len = self
+            .words
+            .iter()
+            .map(|word| word.word.chars().count() + word.result.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for word in &self.words {
+            // Verdict in daughter language, if validated
+            let verdict = match &word.violation {
+                _ if !self.is_validated => String::new(),
+                None => stylize!("valid": Green if do_color),
+                Some(Violation { note, location, .. }) => format!(
+                    "{} {}",
+                    stylize!("INVALID": Red + bold if do_color),
+                    match note {
+                        Some(Note(note)) => stylize!("{}": Blue if do_color, note),
+                        None => stylize!("(at {})": + dim if do_color, location),
+                    }
+                ),
+            };
+
+            let line = format!(
+                "  {} {} {}{}  {}",
+                word.word,
+                stylize!("→": Cyan if do_color),
+                stylize!("{}": + bold if do_color, word.result),
+                " ".repeat(max_len - word.word.chars().count() - word.result.chars().count()),
+                verdict,
+            );
+            // Spacing is not kept without a verdict
+            writeln!(out, "{}", line.trim_end())?;
+
+            // Every change which altered the word
+            for step in &word.history {
+                writeln!(
+                    out,
+                    "      {}  {} {}",
+                    step.rule,
+                    stylize!("(at {})": + dim if do_color, step.location),
+                    stylize!("→ {}": + italic if do_color, step.word),
+                )?;
+            }
+        }
+
+        // Amount of invalid words
+        if self.is_validated {
+            let invalid = self.invalid_count();
+            if invalid == 0 {
+                writeln_styles!(out, "All evolved words are valid": Green + bold if do_color)?;
+            } else {
+                writeln_styles!(
+                    out,
+                    "{} evolved word{} invalid": Red + bold if do_color,
+                    invalid, if invalid == 1 { " is" } else { "s are" }
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Draft {
+    /// Evolve words with every sound change of the draft, in order
+    ///
+    /// If `keep_history` is true, every change which altered each word is kept, with the form of the word after it.
+    /// Returns an error if a change fails to run on a word (such as exceeding the backtrack limit)
+    pub fn evolve(&self, words: &[&str], keep_history: bool) -> Result<Evolution, Error> {
+        let words = words
+            .iter()
+            .map(|word| {
+                let mut result = word.to_string();
+                let mut history = Vec::new();
+
+                for change in &self.changes {
+                    let next = change.apply(&result)?;
+
+                    if keep_history && next != result {
+                        history.push(ChangeStep {
+                            rule: change.rule.clone(),
+                            location: change.location.clone(),
+                            word: next.clone(),
+                        });
+                    }
+                    result = next;
+                }
+
+                Ok(EvolvedWord {
+                    word: word.to_string(),
+                    result,
+                    history,
+                    violation: None,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Evolution {
+            words,
+            is_validated: false,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+// Spans of words are lists of ranges, which often have only one element
+#![allow(clippy::single_range_in_vec_init)]
+
+use super::*;
+
+#[test]
+fn evolve_works() {
+    let draft = Draft::from(
+        "
+        $C = [ptk]
+        $V = [aeiou]
+        @change a > e / _<C>i
+        @change k > t͡ʃ / _[ie]
+        @change <V> > ∅ / <C>_#
+        ",
+    )
+    .unwrap();
+
+    let evolution = draft.evolve(&["taki", "kapa", "mut"], false).unwrap();
+    let results: Vec<_> = evolution
+        .words
+        .iter()
+        .map(|word| (word.word.as_str(), word.result.as_str()))
+        .collect();
+    assert_eq!(
+        results,
+        [("taki", "tet͡ʃi"), ("kapa", "kap"), ("mut", "mut")]
+    );
+    assert!(evolution.words.iter().all(|word| word.history.is_empty()));
+    assert!(!evolution.is_validated);
+
+    // Only changes which altered the word are kept
+    let evolution = draft.evolve(&["taki", "mut"], true).unwrap();
+    assert_eq!(
+        evolution.words[0].history,
+        [
+            ChangeStep {
+                rule: "a > e / _<C>i".to_string(),
+                location: draft.changes[0].location.clone(),
+                word: "teki".to_string(),
+            },
+            ChangeStep {
+                rule: "k > t͡ʃ / _[ie]".to_string(),
+                location: draft.changes[1].location.clone(),
+                word: "tet͡ʃi".to_string(),
+            },
+        ]
+    );
+    assert_eq!(evolution.words[1].history, []);
+
+    // Draft without changes keeps every word
+    let draft = Draft::from("").unwrap();
+    assert_eq!(
+        draft.evolve(&["kato"], true).unwrap().words[0].result,
+        "kato"
+    );
+}
+
+#[test]
+fn validate_works() {
+    let changes = Draft::from("@change p > f; @change a > ∅ / _#").unwrap();
+    let daughter = Draft::from("* Final consonants\n! [pfk]$\n! x").unwrap();
+
+    let mut evolution = changes.evolve(&["papa", "kapi", "pak"], false).unwrap();
+    evolution.validate(&daughter).unwrap();
+
+    assert!(evolution.is_validated);
+    assert_eq!(evolution.invalid_count(), 2);
+
+    assert_eq!(evolution.words[0].result, "faf");
+    assert_eq!(
+        evolution.words[0].violation,
+        Some(Violation {
+            rule: 0,
+            note: Some(Note("Final consonants".to_string())),
+            intent: false,
+            location: daughter.rules[0].location.clone(),
+            spans: vec![2..3],
+        })
+    );
+    assert_eq!(evolution.words[1].violation, None);
+    assert!(evolution.words[2].violation.is_some());
+}
+
+#[test]
+fn display_works() {
+    let changes = Draft::from("@change p > f; @change a > ∅ / _#").unwrap();
+    let daughter = Draft::from("* Final consonants\n! [pfk]$").unwrap();
+
+    let mut evolution = changes.evolve(&["papa", "kapi"], true).unwrap();
+
+    let display = |evolution: &Evolution| {
+        let mut output = Vec::new();
+        evolution.display_with(&mut output, false).unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    assert_eq!(
+        display(&evolution),
+        "Evolving 2 words...
+  papa → faf
+      p > f  (at line 1) → fafa
+      a > ∅ / _#  (at line 1) → faf
+  kapi → kafi
+      p > f  (at line 1) → kafi
+"
+    );
+
+    evolution.validate(&daughter).unwrap();
+    assert_eq!(
+        display(&evolution),
+        "Evolving 2 words...
+  papa → faf   INVALID Final consonants
+      p > f  (at line 1) → fafa
+      a > ∅ / _#  (at line 1) → faf
+  kapi → kafi  valid
+      p > f  (at line 1) → kafi
+1 evolved word is invalid
+"
+    );
+}
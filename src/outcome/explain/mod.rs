@@ -0,0 +1,235 @@
+#[cfg(test)]
+mod tests;
+
+use std::ops::Range;
+
+use stilo::{stylize, writeln_styles};
+
+use super::run::match_spans;
+use crate::{
+    draft::{Draft, Note, Rule},
+    error::Location,
+};
+
+/// Step-by-step evaluation of every rule against a single word
+///
+/// Use `Draft::explain` to create
+///
+/// # Examples
+///
+/// ```
+/// # use phonet::{Draft, outcome::RuleVerdict};
+/// let draft = Draft::from("
+///   $V = [aeiou]
+///   * Invalid letters
+///     + ^ [ptk<V>]+ $
+///   * Vowel clusters
+///     ! <V>{2}
+/// ").unwrap();
+///
+/// let explanation = draft.explain("taaso");
+///
+/// assert!(!explanation.is_valid());
+/// assert_eq!(explanation.traces[0].verdict, RuleVerdict::Violated);
+/// assert_eq!(explanation.traces[1].spans, vec![1..3]);
+///
+/// explanation.display(true); // Prints every rule to stdout
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Explanation {
+    /// Word which was explained
+    pub word: String,
+    /// Trace of every rule, in order of definition
+    pub traces: Vec<RuleTrace>,
+}
+
+/// Evaluation of a single rule against a word
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleTrace {
+    /// Index of rule in `Draft::rules`
+    pub rule: usize,
+    /// Location of rule in file
+    pub location: Location,
+    /// Regex pattern of rule, with classes substituted
+    pub pattern: String,
+    /// Whether pattern should match or not, for word to be valid
+    pub intent: bool,
+    /// Note for rule (optional)
+    pub note: Option<Note>,
+    /// Whether pattern matched the word
+    ///
+    /// `None` if the rule failed to run on the word
+    pub matched: Option<bool>,
+    /// Character ranges of every match of pattern in word
+    pub spans: Vec<Range<usize>>,
+    /// Whether the word satisfies the rule
+    pub verdict: RuleVerdict,
+}
+
+/// Whether a word satisfies a single rule
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RuleVerdict {
+    /// Pattern matched (or did not match) as intended
+    Satisfied,
+    /// Pattern did not match (or matched) as intended, so the word is invalid
+    Violated,
+    /// Rule failed to run on the word (such as exceeding the backtrack limit)
+    ///
+    /// Holds error message
+    Failed(String),
+}
+
+impl Explanation {
+    /// Evaluate every rule against a word, without stopping at the first violation
+    pub fn new(word: &str, rules: &[Rule]) -> Self {
+        let traces = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                // Check for match, and whether match satisfies rule
+                let (matched, verdict) = match rule.pattern.is_match(word) {
+                    Ok(is_match) if rule.intent == is_match => {
+                        (Some(is_match), RuleVerdict::Satisfied)
+                    }
+                    Ok(is_match) => (Some(is_match), RuleVerdict::Violated),
+                    Err(err) => (None, RuleVerdict::Failed(err.to_string())),
+                };
+
+                RuleTrace {
+                    rule: index,
+                    location: rule.location.clone(),
+                    pattern: rule.pattern.as_str().to_string(),
+                    intent: rule.intent,
+                    note: rule.note.clone(),
+                    matched,
+                    spans: match matched {
+                        Some(true) => match_spans(word, &rule.pattern),
+                        _ => Vec::new(),
+                    },
+                    verdict,
+                }
+            })
+            .collect();
+
+        Self {
+            word: word.to_string(),
+            traces,
+        }
+    }
+
+    /// Returns `true` if the word satisfies every rule
+    pub fn is_valid(&self) -> bool {
+        self.traces
+            .iter()
+            .all(|trace| trace.verdict == RuleVerdict::Satisfied)
+    }
+
+    /// Get the first rule which the word does not satisfy, if any
+    ///
+    /// This is the rule which gives the reason for a failing test
+    pub fn first_unsatisfied(&self) -> Option<&RuleTrace> {
+        self.traces
+            .iter()
+            .find(|trace| trace.verdict != RuleVerdict::Satisfied)
+    }
+
+    /// Display explanation to standard output
+    pub fn display(&self, do_color: bool) {
+        self.display_with(&mut std::io::stdout(), do_color)
+            .expect("Could not write to stdout");
+    }
+
+    /// Display explanation, using custom output
+    pub fn display_with(
+        &self,
+        out: &mut dyn std::io::Write,
+        do_color: bool,
+    ) -> Result<(), std::io::Error> {
+        writeln_styles!(out, "Explaining '{}'...": Yellow if do_color, self.word)?;
+
+        for trace in &self.traces {
+            // Rule, with location and note
+            writeln!(
+                out,
+                "  {operator} {pattern}  {location}{note}",
+                operator = if trace.intent {
+                    stylize!("+": Cyan if do_color)
+                } else {
+                    stylize!("!": Magenta if do_color)
+                },
+                pattern = trace.pattern,
+                location = stylize!("(at {})": + dim if do_color, trace.location),
+                note = match &trace.note {
+                    Some(Note(note)) => format!(" {}", stylize!("{}": Blue if do_color, note)),
+                    None => String::new(),
+                },
+            )?;
+
+            // Match, with spans
+            let matched = match trace.matched {
+                Some(true) => format!(
+                    "matched {}",
+                    trace
+                        .spans
+                        .iter()
+                        .map(|span| format!(
+                            "'{}' ({}..{})",
+                            self.word
+                                .chars()
+                                .skip(span.start)
+                                .take(span.len())
+                                .collect::<String>(),
+                            span.start,
+                            span.end
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                Some(false) => "no match".to_string(),
+                None => "not ran".to_string(),
+            };
+
+            // Verdict of rule
+            let verdict = match &trace.verdict {
+                RuleVerdict::Satisfied => stylize!("satisfied": Green if do_color),
+                RuleVerdict::Violated => stylize!("VIOLATED": Red + bold if do_color),
+                RuleVerdict::Failed(err) => {
+                    stylize!("FAILED - {}": Red + bold if do_color, err)
+                }
+            };
+
+            writeln!(out, "      {} -> {}", matched, verdict)?;
+        }
+
+        // Final verdict
+        if self.is_valid() {
+            writeln_styles!(out, "'{}' is valid": Green + bold if do_color, self.word)?;
+        } else {
+            let count = self
+                .traces
+                .iter()
+                .filter(|trace| trace.verdict != RuleVerdict::Satisfied)
+                .count();
+
+            writeln_styles!(
+                out,
+                "'{}' is invalid ({} rule{} not satisfied)": Red + bold if do_color,
+                self.word, count, if count == 1 { "" } else { "s" }
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Draft {
+    /// Evaluate every rule against a word, step by step
+    ///
+    /// Unlike `Draft::run`, this does not stop at the first violated rule
+    pub fn explain(&self, word: &str) -> Explanation {
+        Explanation::new(word, &self.rules)
+    }
+}
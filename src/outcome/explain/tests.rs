@@ -0,0 +1,96 @@
+use super::*;
+
+#[test]
+fn explain_works() {
+    let draft = Draft::from(
+        "$V = [aeiou]\n* Invalid letters\n+ ^[ptk<V>]+$\n* Vowel clusters\n! <V>{2}\n! (.)\\1",
+    )
+    .unwrap();
+
+    let explanation = draft.explain("taaso");
+
+    assert_eq!(explanation.word, "taaso");
+    assert!(!explanation.is_valid());
+
+    let mut traces = explanation.traces.iter();
+
+    // Letter `s` is not allowed
+    assert_eq!(
+        traces.next(),
+        Some(&RuleTrace {
+            rule: 0,
+            location: draft.rules[0].location.clone(),
+            pattern: "^[ptk[aeiou]]+$".to_string(),
+            intent: true,
+            note: Some(Note("Invalid letters".to_string())),
+            matched: Some(false),
+            spans: vec![],
+            verdict: RuleVerdict::Violated,
+        })
+    );
+
+    // Vowel cluster is not allowed
+    let trace = traces.next().unwrap();
+    assert_eq!(trace.location.line, 5);
+    assert_eq!(trace.pattern, "(?:[aeiou]){2}");
+    assert_eq!(trace.matched, Some(true));
+    assert_eq!(trace.spans, vec![1..3]);
+    assert_eq!(trace.verdict, RuleVerdict::Violated);
+
+    // Every rule is checked, even after the first violation
+    let trace = traces.next().unwrap();
+    assert_eq!(trace.location.line, 6);
+    assert_eq!(trace.spans, vec![1..3]);
+    assert_eq!(trace.verdict, RuleVerdict::Violated);
+
+    assert_eq!(traces.next(), None);
+
+    // First unsatisfied rule is the reason given by `Draft::run`
+    assert_eq!(
+        explanation.first_unsatisfied().map(|trace| trace.rule),
+        Some(0)
+    );
+
+    // Valid word
+    let explanation = draft.explain("tako");
+    assert!(explanation.is_valid());
+    assert_eq!(explanation.first_unsatisfied(), None);
+}
+
+#[test]
+fn explain_returns_failed_rule() {
+    let draft = Draft::from("@backtrack-limit 100\n! (a*)*\\1b").unwrap();
+
+    let explanation = draft.explain("aaaaaaaaaaaaaaaaaaaa");
+
+    assert!(!explanation.is_valid());
+    assert_eq!(explanation.traces[0].matched, None);
+    assert!(matches!(
+        explanation.traces[0].verdict,
+        RuleVerdict::Failed(_)
+    ));
+}
+
+#[test]
+fn display_explanation_works() {
+    let draft = Draft::from("* Vowel clusters\n! [aeiou]{2}\n+ ^[ptkaeiou]+$").unwrap();
+
+    let mut out = Vec::new();
+    draft
+        .explain("taakaa")
+        .display_with(&mut out, false)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        [
+            "Explaining 'taakaa'...",
+            "  ! [aeiou]{2}  (at line 2) Vowel clusters",
+            "      matched 'aa' (1..3), 'aa' (4..6) -> VIOLATED",
+            "  + ^[ptkaeiou]+$  (at line 3) Vowel clusters",
+            "      matched 'taakaa' (0..6) -> satisfied",
+            "'taakaa' is invalid (1 rule not satisfied)\n",
+        ]
+        .join("\n")
+    );
+}
@@ -0,0 +1,265 @@
+#[cfg(test)]
+mod tests;
+
+use super::{Outcome, PassStatus, TestOutcome, Violation};
+use crate::draft::{Message::*, Note};
+
+/// Name of section of tests, which are not after any note
+const DEFAULT_SECTION: &str = "Tests";
+
+/// Tests grouped under the note before them
+type Section<'a> = (Option<&'a Note>, Vec<&'a TestOutcome>);
+
+impl Outcome {
+    /// Get results as a JSON string
+    ///
+    /// Tests are grouped into sections, by the note before them
+    pub fn to_json(&self) -> String {
+        let sections: Vec<String> = self
+            .sections()
+            .iter()
+            .map(|(note, tests)| {
+                format!(
+                    "{{\"note\":{},\"tests\":[{}]}}",
+                    json_option(note.map(|Note(note)| note.as_str())),
+                    tests
+                        .iter()
+                        .map(|test| test_to_json(test))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"test_count\":{},\"fail_count\":{},\"sections\":[{}]}}",
+            self.test_count(),
+            self.fail_count,
+            sections.join(",")
+        )
+    }
+
+    /// Get results in Test Anything Protocol (TAP) format, version 13
+    ///
+    /// Notes are written as comments
+    pub fn to_tap(&self) -> String {
+        let mut lines = vec![
+            "TAP version 13".to_string(),
+            format!("1..{}", self.test_count()),
+        ];
+
+        let mut number = 0;
+        for msg in &self.messages {
+            match msg {
+                Info(Note(note)) => lines.push(format!("# {}", note)),
+
+                Test(test) => {
+                    number += 1;
+
+                    let PassStatus::Fail(kind) = &test.status else {
+                        lines.push(format!("ok {} - {}", number, test_name(test)));
+                        continue;
+                    };
+
+                    lines.push(format!("not ok {} - {}", number, test_name(test)));
+
+                    // Diagnostic of failure, as YAML
+                    lines.push("  ---".to_string());
+                    lines.push(format!("  message: {}", json_string(&kind.to_string())));
+                    lines.push(format!("  kind: {}", kind.name()));
+                    for Violation { location, .. } in &test.violations {
+                        lines.push(format!("  rule: {}", json_string(&location.to_string())));
+                    }
+                    lines.push("  ...".to_string());
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// Get results in JUnit XML format
+    ///
+    /// Tests are grouped into test suites, by the note before them
+    pub fn to_junit(&self) -> String {
+        let mut lines = vec![
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+            format!(
+                r#"<testsuites name="phonet" tests="{}" failures="{}">"#,
+                self.test_count(),
+                self.fail_count
+            ),
+        ];
+
+        for (note, tests) in self.sections() {
+            // Skip notes without tests
+            if tests.is_empty() {
+                continue;
+            }
+
+            let name = xml_escape(note.map_or(DEFAULT_SECTION, |Note(note)| note.as_str()));
+            let fail_count = tests.iter().filter(|test| test.status.is_fail()).count();
+
+            lines.push(format!(
+                r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+                name,
+                tests.len(),
+                fail_count
+            ));
+
+            for test in tests {
+                let case = format!(
+                    r#"<testcase name="{}" classname="{}""#,
+                    xml_escape(&test_name(test)),
+                    name
+                );
+
+                let PassStatus::Fail(kind) = &test.status else {
+                    lines.push(format!("    {}/>", case));
+                    continue;
+                };
+
+                // Locations of rules which word violated
+                let rules: Vec<_> = test
+                    .violations
+                    .iter()
+                    .map(|violation| format!("Rule at {}", violation.location))
+                    .collect();
+
+                lines.push(format!("    {}>", case));
+                lines.push(format!(
+                    r#"      <failure message="{}" type="{}">{}</failure>"#,
+                    xml_escape(&kind.to_string()),
+                    kind.name(),
+                    xml_escape(&rules.join("\n")),
+                ));
+                lines.push("    </testcase>".to_string());
+            }
+
+            lines.push("  </testsuite>".to_string());
+        }
+
+        lines.push("</testsuites>".to_string());
+        lines.join("\n")
+    }
+
+    /// Group tests into sections, by the note before them
+    ///
+    /// Tests before any note are grouped into a section without a note, if any exist
+    fn sections(&self) -> Vec<Section<'_>> {
+        let mut sections: Vec<Section> = Vec::new();
+
+        for msg in &self.messages {
+            match msg {
+                Info(note) => sections.push((Some(note), Vec::new())),
+
+                Test(test) => match sections.last_mut() {
+                    Some((_, tests)) => tests.push(test),
+                    None => sections.push((None, vec![test])),
+                },
+            }
+        }
+
+        sections
+    }
+}
+
+/// Get name of test, with its intent
+fn test_name(test: &TestOutcome) -> String {
+    format!(
+        "{}{} ({})",
+        test.underlying_prefix(),
+        test.word,
+        if test.intent { "valid" } else { "invalid" }
+    )
+}
+
+/// Convert test outcome to JSON object
+fn test_to_json(test: &TestOutcome) -> String {
+    let (status, kind, reason) = match &test.status {
+        PassStatus::Pass => ("pass", None, None),
+        PassStatus::Fail(kind) => ("fail", Some(kind.name()), Some(kind.to_string())),
+    };
+
+    let violations: Vec<String> = test
+        .violations
+        .iter()
+        .map(|violation| {
+            format!(
+                "{{\"line\":{},\"file\":{},\"intent\":{},\"note\":{},\"spans\":[{}]}}",
+                violation.location.line,
+                json_option(
+                    violation
+                        .location
+                        .file
+                        .as_ref()
+                        .map(|file| file.to_string_lossy())
+                        .as_deref()
+                ),
+                violation.intent,
+                json_option(violation.note.as_ref().map(|Note(note)| note.as_str())),
+                violation
+                    .spans
+                    .iter()
+                    .map(|span| format!("[{},{}]", span.start, span.end))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        })
+        .collect();
+
+    // Underlying form is only included if word was derived
+    let underlying = match &test.underlying {
+        Some(underlying) => format!(",\"underlying\":{}", json_string(underlying)),
+        None => String::new(),
+    };
+
+    format!(
+        "{{\"word\":{}{},\"intent\":{},\"status\":\"{}\",\"kind\":{},\"reason\":{},\"violations\":[{}]}}",
+        json_string(&test.word),
+        underlying,
+        test.intent,
+        status,
+        json_option(kind),
+        json_option(reason.as_deref()),
+        violations.join(",")
+    )
+}
+
+/// Convert optional string to JSON string, or `null`
+fn json_option(string: Option<&str>) -> String {
+    match string {
+        Some(string) => json_string(string),
+        None => "null".to_string(),
+    }
+}
+
+/// Convert string to JSON string, with quotes, escaping special characters
+fn json_string(string: &str) -> String {
+    let mut escaped = String::from('"');
+
+    for ch in string.chars() {
+        match ch {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            '\r' => escaped += "\\r",
+            '\t' => escaped += "\\t",
+            ch if (ch as u32) < 0x20 => escaped += &format!("\\u{:04x}", ch as u32),
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// Escape special characters of XML text or attribute
+fn xml_escape(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
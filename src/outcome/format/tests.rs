@@ -0,0 +1,110 @@
+use super::*;
+use crate::Draft;
+
+/// Get outcome of example draft, with a passing and a failing test in each section
+fn example_outcome() -> Outcome {
+    Draft::from("?+ ka\n* Invalid <letters>\n+ ^[ptka]+$\n?+ taka\n?+ \"x\"")
+        .unwrap()
+        .run()
+}
+
+#[test]
+fn json_string_works() {
+    assert_eq!(json_string("abc"), r#""abc""#);
+    assert_eq!(json_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+    assert_eq!(json_string("ŋ\u{1}"), r#""ŋ\u0001""#);
+}
+
+#[test]
+fn xml_escape_works() {
+    assert_eq!(xml_escape("abc"), "abc");
+    assert_eq!(
+        xml_escape(r#"<a href="x">'b' & c</a>"#),
+        "&lt;a href=&quot;x&quot;&gt;&apos;b&apos; &amp; c&lt;/a&gt;"
+    );
+}
+
+#[test]
+fn sections_works() {
+    let outcome = example_outcome();
+
+    let sections: Vec<_> = outcome
+        .sections()
+        .into_iter()
+        .map(|(note, tests)| {
+            (
+                note.map(|Note(note)| note.as_str()),
+                tests.iter().map(|test| test.word.as_str()).collect(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        sections,
+        vec![
+            (None, vec!["ka"]),
+            (Some("Invalid <letters>"), vec!["taka", "\"x\""]),
+        ]
+    );
+}
+
+#[test]
+fn to_json_works() {
+    assert_eq!(
+        example_outcome().to_json(),
+        concat!(
+            r#"{"test_count":3,"fail_count":1,"sections":["#,
+            r#"{"note":null,"tests":["#,
+            r#"{"word":"ka","intent":true,"status":"pass","kind":null,"reason":null,"violations":[]}"#,
+            r#"]},"#,
+            r#"{"note":"Invalid <letters>","tests":["#,
+            r#"{"word":"taka","intent":true,"status":"pass","kind":null,"reason":null,"violations":[]},"#,
+            r#"{"word":"\"x\"","intent":true,"status":"fail","kind":"custom_reason","reason":"Invalid <letters>","#,
+            r#""violations":[{"line":3,"file":null,"intent":true,"note":"Invalid <letters>","spans":[]}]}"#,
+            r#"]}]}"#,
+        )
+    );
+}
+
+#[test]
+fn to_tap_works() {
+    assert_eq!(
+        example_outcome().to_tap(),
+        [
+            "TAP version 13",
+            "1..3",
+            "ok 1 - ka (valid)",
+            "# Invalid <letters>",
+            "ok 2 - taka (valid)",
+            "not ok 3 - \"x\" (valid)",
+            "  ---",
+            "  message: \"Invalid <letters>\"",
+            "  kind: custom_reason",
+            "  rule: \"line 3\"",
+            "  ...",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn to_junit_works() {
+    assert_eq!(
+        example_outcome().to_junit(),
+        [
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<testsuites name="phonet" tests="3" failures="1">"#,
+            r#"  <testsuite name="Tests" tests="1" failures="0">"#,
+            r#"    <testcase name="ka (valid)" classname="Tests"/>"#,
+            r#"  </testsuite>"#,
+            r#"  <testsuite name="Invalid &lt;letters&gt;" tests="2" failures="1">"#,
+            r#"    <testcase name="taka (valid)" classname="Invalid &lt;letters&gt;"/>"#,
+            r#"    <testcase name="&quot;x&quot; (valid)" classname="Invalid &lt;letters&gt;">"#,
+            r#"      <failure message="Invalid &lt;letters&gt;" type="custom_reason">Rule at line 3</failure>"#,
+            r#"    </testcase>"#,
+            r#"  </testsuite>"#,
+            r#"</testsuites>"#,
+        ]
+        .join("\n")
+    );
+}
@@ -1,11 +1,27 @@
 /// Display function for `Outcome` struct
 mod display;
+/// Evolution of words with sound changes
+mod evolve;
+/// Step-by-step evaluation of rules against a word
+mod explain;
+/// Machine-readable output formats for `Outcome` struct
+mod format;
 /// Run function for `Outcome` struct
 mod run;
 
+use std::{fmt, ops::Range};
+
 pub(crate) use self::run::{validate_test, Validity};
+pub use self::{
+    evolve::{ChangeStep, Evolution, EvolvedWord},
+    explain::{Explanation, RuleTrace, RuleVerdict},
+    run::EvalMode,
+};
 
-use crate::draft::{Message, Note};
+use crate::{
+    draft::{Message, Note},
+    error::Location,
+};
 
 /// Outcome of tests ran from *Phonet* `Draft`
 ///
@@ -33,6 +49,7 @@ use crate::draft::{Message, Note};
 /// outcome.display(Default::default(), true); // Prints results to stdout
 /// ```
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Outcome {
     /// Messages to display
     pub messages: Vec<Message<TestOutcome>>,
@@ -42,17 +59,44 @@ pub struct Outcome {
 
 /// Outcome of `TestDraft` that was ran
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TestOutcome {
     /// String that was tested
     pub word: String,
+    /// Broad transcription which the word was derived from with allophones (optional)
+    pub underlying: Option<String>,
     /// Whether test should have been valid or not to pass
     pub intent: bool,
     /// Whether the test has passed or not
     pub status: PassStatus,
+    /// Rules which the word was invalid against
+    ///
+    /// Only includes the first violated rule, unless ran with `EvalMode::AllViolations`
+    pub violations: Vec<Violation>,
+}
+
+/// Rule which a word was invalid against
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Violation {
+    /// Index of rule in `Draft::rules`
+    pub rule: usize,
+    /// Note of rule (optional)
+    pub note: Option<Note>,
+    /// Whether rule pattern should have matched or not
+    pub intent: bool,
+    /// Location of rule in file
+    pub location: Location,
+    /// Character ranges of word which caused the violation
+    ///
+    /// For `!` rules, this is every match of the pattern.
+    /// For `+` rules, this is the region where matching broke down, if it can be determined
+    pub spans: Vec<Range<usize>>,
 }
 
 /// Status of test that was ran
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PassStatus {
     /// Test passed
     ///
@@ -65,7 +109,11 @@ pub enum PassStatus {
 }
 
 /// The manner in which a test failed
+///
+/// More variants may be added in minor versions
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum FailKind {
     /// The test was supposed to not match the rules, however it did
     ShouldBeInvalid,
@@ -77,12 +125,26 @@ pub enum FailKind {
     ///
     /// A custom reason was given to the rule of which this test failed against
     CustomReason(Note),
+    /// The test was invalid as intended, but for a different reason than expected
+    ///
+    /// Holds expected reason, and actual reason (note of first violated rule) if any
+    WrongReason(Note, Option<Note>),
+    /// A rule failed to run on the test (such as exceeding the backtrack limit)
+    ///
+    /// Holds location of rule, and error message
+    RuleError(Location, String),
+    /// The test passed, but its word was not transcribed as expected
+    ///
+    /// Holds expected transcription, and actual transcription
+    WrongTranscription(String, String),
 }
 
 /// The kinds of messages to display to the output, when `Outcome::display` is called
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisplayLevel {
     /// Show everything: passed or failed tests, and notes
+    #[default]
     ShowAll,
     /// Show failed tests and notes, but not passes
     IgnorePasses,
@@ -92,6 +154,56 @@ pub enum DisplayLevel {
     HideAll,
 }
 
+impl FailKind {
+    /// Get name of kind, in snake case
+    ///
+    /// For machine-readable output formats
+    pub fn name(&self) -> &'static str {
+        use FailKind::*;
+
+        match self {
+            ShouldBeInvalid => "should_be_invalid",
+            NoReasonGiven => "no_reason_given",
+            CustomReason(_) => "custom_reason",
+            WrongReason(..) => "wrong_reason",
+            RuleError(..) => "rule_error",
+            WrongTranscription(..) => "wrong_transcription",
+        }
+    }
+}
+
+impl fmt::Display for FailKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FailKind::*;
+
+        match self {
+            ShouldBeInvalid => write!(f, "Valid, but should be invalid"),
+            NoReasonGiven => write!(f, "No reason given"),
+            CustomReason(Note(reason)) => write!(f, "{}", reason),
+            WrongReason(Note(expected), actual) => match actual {
+                Some(Note(actual)) => write!(f, "Expected '{}', but was '{}'", expected, actual),
+                None => write!(f, "Expected '{}', but was no reason given", expected),
+            },
+            RuleError(location, err) => write!(f, "Rule failed at {} - {}", location, err),
+            WrongTranscription(expected, actual) => {
+                write!(f, "Expected /{}/, but was /{}/", expected, actual)
+            }
+        }
+    }
+}
+
+impl TestOutcome {
+    /// Get underlying broad transcription before word, if word was derived with allophones, such as `/kato/ → `
+    ///
+    /// Blank if word was not derived
+    pub fn underlying_prefix(&self) -> String {
+        match &self.underlying {
+            Some(underlying) => format!("/{}/ → ", underlying),
+            None => String::new(),
+        }
+    }
+}
+
 impl PassStatus {
     /// Returns `true` if self is `Pass`
     pub fn is_pass(&self) -> bool {
@@ -103,9 +215,3 @@ impl PassStatus {
         matches!(self, Self::Fail(_))
     }
 }
-
-impl Default for DisplayLevel {
-    fn default() -> Self {
-        Self::ShowAll
-    }
-}
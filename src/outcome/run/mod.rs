@@ -1,16 +1,20 @@
 #[cfg(test)]
 mod tests;
 
+use std::ops::Range;
+
+use fancy_regex::Regex;
+
 use self::Validity::*;
 use super::{
     FailKind::*,
     Outcome,
     PassStatus::{self, *},
-    TestOutcome,
+    TestOutcome, Violation,
 };
 use crate::{
     draft::{Draft, Message::*, Note, Rule, TestDraft},
-    REGEX_MATCH_FAIL,
+    error::Error,
 };
 
 /// Whether test *matches* or not
@@ -19,12 +23,31 @@ use crate::{
 #[derive(Debug, PartialEq)]
 pub(crate) enum Validity {
     Valid,
-    Invalid(Option<Note>),
+    /// Holds rules which were violated (never empty)
+    Invalid(Vec<Violation>),
+}
+
+/// How many rules to check, once a word is found to be invalid
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EvalMode {
+    /// Stop at the first rule which the word violates
+    #[default]
+    FirstViolation,
+    /// Check every rule, to report all rules which the word violates
+    AllViolations,
 }
 
 impl Outcome {
     /// Run drafted tests, return `Output`
+    ///
+    /// Only the first violated rule is reported for each test. Use `Outcome::run_with` to report all
     pub fn run(draft: &Draft) -> Self {
+        Self::run_with(draft, EvalMode::default())
+    }
+
+    /// Run drafted tests with an evaluation mode, return `Output`
+    pub fn run_with(draft: &Draft, eval_mode: EvalMode) -> Self {
         // No messages
         if draft.messages.is_empty() {
             return Self {
@@ -44,7 +67,15 @@ impl Outcome {
                 Info(note) => Info(note.clone()),
                 // Run test
                 Test(test) => {
-                    let outcome = run_test(test.clone(), &draft.rules);
+                    let mut outcome = run_test(test.clone(), &draft.rules, eval_mode);
+
+                    // Check transcription of word, if test otherwise passed
+                    if let (Pass, Some(expected)) = (&outcome.status, &test.transcription) {
+                        let actual = draft.transcribe(&test.word);
+                        if actual != *expected {
+                            outcome.status = Fail(WrongTranscription(expected.clone(), actual));
+                        }
+                    }
 
                     // Increase fail count if failed
                     if outcome.status.is_fail() {
@@ -64,45 +95,185 @@ impl Outcome {
 }
 
 /// Run `TestDraft` against rules, return `TestOutcome`
-fn run_test(test: TestDraft, rules: &[Rule]) -> TestOutcome {
-    // Validate test
-    let validity = validate_test(&test.word, rules);
+fn run_test(test: TestDraft, rules: &[Rule], eval_mode: EvalMode) -> TestOutcome {
+    // Validate test, and get status
+    let (status, violations) = match validate_test(&test.word, rules, eval_mode) {
+        Ok(validity) => {
+            let status = get_status(&validity, test.intent, test.reason.as_ref());
 
-    // Get status
-    let status = get_status(validity, test.intent);
+            let violations = match validity {
+                Valid => Vec::new(),
+                // Find which part of word caused each violation
+                Invalid(violations) => violations
+                    .into_iter()
+                    .map(|violation| Violation {
+                        spans: violation_spans(&test.word, &rules[violation.rule]),
+                        ..violation
+                    })
+                    .collect(),
+            };
+
+            (status, violations)
+        }
+
+        // Rule failed to run on word
+        Err(Error::RuleFail(_, location, err)) => {
+            (Fail(RuleError(location, err.to_string())), Vec::new())
+        }
+        Err(err) => (
+            Fail(RuleError(Default::default(), err.to_string())),
+            Vec::new(),
+        ),
+    };
 
     TestOutcome {
         intent: test.intent,
         word: test.word,
+        underlying: test.underlying,
         status,
+        violations,
     }
 }
 
 /// Check if test is valid against rules
-pub(crate) fn validate_test(word: &str, rules: &[Rule]) -> Validity {
-    // Check for match with every rule, if not, return fail
-    for Rule {
-        intent,
-        pattern,
-        note,
-    } in rules
+///
+/// Returns an error if any rule fails to run on the word (such as exceeding the backtrack limit)
+pub(crate) fn validate_test(
+    word: &str,
+    rules: &[Rule],
+    eval_mode: EvalMode,
+) -> Result<Validity, Error> {
+    let mut violations = Vec::new();
+
+    // Check for match with every rule, if not, add violation
+    for (
+        index,
+        Rule {
+            intent,
+            pattern,
+            note,
+            location,
+            ..
+        },
+    ) in rules.iter().enumerate()
     {
-        // Check if rule matches, and whether match signifies returning invalid or continuing
-        if intent ^ pattern.is_match(word).expect(REGEX_MATCH_FAIL) {
-            return Invalid(note.clone());
+        let is_match = pattern
+            .is_match(word)
+            .map_err(|err| Error::RuleFail(word.to_string(), location.clone(), Box::new(err)))?;
+
+        // Check if rule matches, and whether match signifies invalid or continuing
+        if intent ^ is_match {
+            // Spans are found later, only if required
+            violations.push(Violation {
+                rule: index,
+                note: note.clone(),
+                intent: *intent,
+                location: location.clone(),
+                spans: Vec::new(),
+            });
+
+            // Stop at first violation, unless all are required
+            if eval_mode == EvalMode::FirstViolation {
+                break;
+            }
         }
     }
 
-    Valid
+    Ok(if violations.is_empty() {
+        Valid
+    } else {
+        Invalid(violations)
+    })
 }
 
-/// Get `PassStatus` from `Validity` and test `intent`
-fn get_status(validity: Validity, intent: bool) -> PassStatus {
+/// Get character ranges of word which caused a violation of rule
+///
+/// For `!` rules, returns every match of the pattern
+///
+/// For `+` rules, returns the region between the longest prefix and the longest suffix of the word, which
+/// both match the pattern alone. Returns nothing if no prefix or suffix matches
+pub(super) fn violation_spans(word: &str, rule: &Rule) -> Vec<Range<usize>> {
+    // Every match of rule
+    if !rule.intent {
+        return match_spans(word, &rule.pattern);
+    }
+
+    // Convert byte index to character index
+    let char_index = |byte: usize| word[..byte].chars().count();
+
+    // Byte indexes of every character boundary
+    let boundaries: Vec<usize> = word
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([word.len()])
+        .collect();
+
+    // Check if part of word matches rule (errors count as non-matching)
+    let is_match = |part: &str| rule.pattern.is_match(part).unwrap_or(false);
+
+    // Longest prefix which matches, and start of longest suffix which matches
+    let start = boundaries
+        .iter()
+        .rev()
+        .find(|index| is_match(&word[..**index]))
+        .map(|index| char_index(*index));
+    let end = boundaries
+        .iter()
+        .find(|index| is_match(&word[**index..]))
+        .map(|index| char_index(*index));
+
+    let length = word.chars().count();
+
+    // Region cannot be determined
+    if start.is_none() && end.is_none() {
+        return Vec::new();
+    }
+    let start = start.unwrap_or(0);
+    let end = end.unwrap_or(length);
+
+    let span = if start < end {
+        start..end
+    } else if start < length {
+        // Prefix and suffix overlap, so matching broke down at the end of prefix
+        start..start + 1
+    } else {
+        return Vec::new();
+    };
+
+    vec![span]
+}
+
+/// Get character ranges of every (non-empty) match of pattern in word
+///
+/// Matching stops at the first error
+pub(super) fn match_spans(word: &str, pattern: &Regex) -> Vec<Range<usize>> {
+    // Convert byte index to character index
+    let char_index = |byte: usize| word[..byte].chars().count();
+
+    pattern
+        .find_iter(word)
+        .map_while(Result::ok)
+        .filter(|found| !found.as_str().is_empty())
+        .map(|found| char_index(found.start())..char_index(found.end()))
+        .collect()
+}
+
+/// Get `PassStatus` from `Validity`, test `intent`, and expected reason of test
+fn get_status(validity: &Validity, intent: bool, reason: Option<&Note>) -> PassStatus {
     // Check if validity status matches test intent
     let is_pass = !(matches!(validity, Valid) ^ intent);
 
     // Get status from validity
     if is_pass {
+        // Test is invalid as intended, check reason is as expected
+        if let (Invalid(violations), Some(expected)) = (validity, reason) {
+            let actual = violations.first().and_then(|rule| rule.note.as_ref());
+
+            if actual != Some(expected) {
+                return Fail(WrongReason(expected.clone(), actual.cloned()));
+            }
+        }
+
         // Test passes
         Pass
     } else {
@@ -112,9 +283,10 @@ fn get_status(validity: Validity, intent: bool) -> PassStatus {
             Valid => ShouldBeInvalid,
 
             // Test was invalid, but should have been valid
-            Invalid(reason) => match reason {
+            // Reason is taken from first violated rule
+            Invalid(violations) => match violations.first().and_then(|rule| rule.note.as_ref()) {
                 // Custom reason
-                Some(reason) => CustomReason(reason),
+                Some(reason) => CustomReason(reason.clone()),
                 // No reason was given
                 None => NoReasonGiven,
             },
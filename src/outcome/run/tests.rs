@@ -1,26 +1,65 @@
-use std::collections::HashMap;
+// Spans of words are lists of ranges, which often have only one element
+#![allow(clippy::single_range_in_vec_init)]
 
+use std::{collections::HashMap, ops::Range};
+
+use fancy_regex::Regex;
 use fancy_regex_macro::regex;
 
 use super::*;
-use crate::draft::{Message, Mode};
+use crate::{
+    draft::{Message, Mode, Note},
+    error::Location,
+    outcome::Violation,
+};
 
 /// Get example list of rules for testing
 fn get_example_rules() -> Vec<Rule> {
     vec![
         Rule {
             pattern: regex!("a").clone(),
+            source: "a".to_string(),
             intent: true,
             note: Some(Note("Should contain ⟨a⟩".to_string())),
+            location: Default::default(),
         },
         Rule {
             pattern: regex!("x").clone(),
+            source: "x".to_string(),
             intent: false,
             note: None,
+            location: Default::default(),
         },
     ]
 }
 
+/// Get violations of example rules at each index, with spans of word
+fn example_violations(indices: &[(usize, &[Range<usize>])]) -> Vec<Violation> {
+    let rules = get_example_rules();
+
+    indices
+        .iter()
+        .map(|(index, spans)| Violation {
+            rule: *index,
+            note: rules[*index].note.clone(),
+            intent: rules[*index].intent,
+            location: rules[*index].location.clone(),
+            spans: spans.to_vec(),
+        })
+        .collect()
+}
+
+/// Get invalid validity, with a single violated rule
+fn invalid(note: Option<Note>) -> Validity {
+    Invalid(vec![Violation {
+        rule: 0,
+        note,
+        intent: true,
+        location: Default::default(),
+        spans: Vec::new(),
+    }])
+}
+
 /// All tests should pass
 #[test]
 fn run_all_successful() {
@@ -32,6 +71,9 @@ fn run_all_successful() {
             Test(TestDraft {
                 word: "abc".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
             //
             Info(Note("another note".to_string())),
@@ -39,11 +81,17 @@ fn run_all_successful() {
             Test(TestDraft {
                 word: "ax".to_string(),
                 intent: false,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
             //
             Test(TestDraft {
                 word: "hello".to_string(),
                 intent: false,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
         ],
         //
@@ -51,8 +99,18 @@ fn run_all_successful() {
         mode: Mode::Romanized,
         name: None,
         test_count: 3,
+        backtrack_limit: None,
         //
         raw_classes: HashMap::new(),
+        macros: Default::default(),
+        preludes: vec![],
+        features: vec![],
+        orthography: vec![],
+        raw_allophones: vec![],
+        allophones: vec![],
+        raw_changes: vec![],
+        changes: vec![],
+        classes: HashMap::new(),
         raw_rules: vec![],
     };
 
@@ -71,8 +129,10 @@ fn run_all_successful() {
         list.next(),
         Some(&Message::Test(TestOutcome {
             word: "abc".to_string(),
+            underlying: None,
             intent: true,
-            status: Pass
+            status: Pass,
+            violations: vec![],
         }))
     );
 
@@ -85,8 +145,10 @@ fn run_all_successful() {
         list.next(),
         Some(&Message::Test(TestOutcome {
             word: "ax".to_string(),
+            underlying: None,
             intent: false,
-            status: Pass
+            status: Pass,
+            violations: example_violations(&[(1, &[1..2])]),
         }))
     );
 
@@ -94,8 +156,10 @@ fn run_all_successful() {
         list.next(),
         Some(&Message::Test(TestOutcome {
             word: "hello".to_string(),
+            underlying: None,
             intent: false,
-            status: Pass
+            status: Pass,
+            violations: example_violations(&[(0, &[])]),
         }))
     );
 
@@ -113,6 +177,9 @@ fn run_all_failing() {
             Test(TestDraft {
                 word: "abc".to_string(),
                 intent: false,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
             //
             Info(Note("another note".to_string())),
@@ -120,11 +187,17 @@ fn run_all_failing() {
             Test(TestDraft {
                 word: "ax".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
             //
             Test(TestDraft {
                 word: "hello".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             }),
         ],
         //
@@ -132,8 +205,18 @@ fn run_all_failing() {
         mode: Mode::Romanized,
         name: None,
         test_count: 3,
+        backtrack_limit: None,
         //
         raw_classes: HashMap::new(),
+        macros: Default::default(),
+        preludes: vec![],
+        features: vec![],
+        orthography: vec![],
+        raw_allophones: vec![],
+        allophones: vec![],
+        raw_changes: vec![],
+        changes: vec![],
+        classes: HashMap::new(),
         raw_rules: vec![],
     };
 
@@ -152,8 +235,10 @@ fn run_all_failing() {
         list.next(),
         Some(&Message::Test(TestOutcome {
             word: "abc".to_string(),
+            underlying: None,
             intent: false,
-            status: Fail(ShouldBeInvalid)
+            status: Fail(ShouldBeInvalid),
+            violations: vec![],
         }))
     );
 
@@ -166,8 +251,10 @@ fn run_all_failing() {
         list.next(),
         Some(&Message::Test(TestOutcome {
             word: "ax".to_string(),
+            underlying: None,
             intent: true,
-            status: Fail(NoReasonGiven)
+            status: Fail(NoReasonGiven),
+            violations: example_violations(&[(1, &[1..2])]),
         }))
     );
 
@@ -175,8 +262,10 @@ fn run_all_failing() {
         list.next(),
         Some(&Message::Test(TestOutcome {
             word: "hello".to_string(),
+            underlying: None,
             intent: true,
-            status: Fail(CustomReason(Note("Should contain ⟨a⟩".to_string())))
+            status: Fail(CustomReason(Note("Should contain ⟨a⟩".to_string()))),
+            violations: example_violations(&[(0, &[])]),
         }))
     );
 
@@ -193,13 +282,19 @@ fn run_test_all_valid() {
             TestDraft {
                 word: "abc".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             },
-            &rules
+            &rules,
+            EvalMode::FirstViolation
         ),
         TestOutcome {
             word: "abc".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         }
     );
 
@@ -208,13 +303,19 @@ fn run_test_all_valid() {
             TestDraft {
                 word: "hello".to_string(),
                 intent: false,
+                reason: None,
+                transcription: None,
+                underlying: None,
             },
-            &rules
+            &rules,
+            EvalMode::FirstViolation
         ),
         TestOutcome {
             word: "hello".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: example_violations(&[(0, &[])]),
         }
     );
 
@@ -223,13 +324,19 @@ fn run_test_all_valid() {
             TestDraft {
                 word: "ax".to_string(),
                 intent: false,
+                reason: None,
+                transcription: None,
+                underlying: None,
             },
-            &rules
+            &rules,
+            EvalMode::FirstViolation
         ),
         TestOutcome {
             word: "ax".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: example_violations(&[(1, &[1..2])]),
         }
     );
 }
@@ -244,13 +351,19 @@ fn run_test_all_invalid() {
             TestDraft {
                 word: "abc".to_string(),
                 intent: false,
+                reason: None,
+                transcription: None,
+                underlying: None,
             },
-            &rules
+            &rules,
+            EvalMode::FirstViolation
         ),
         TestOutcome {
             word: "abc".to_string(),
+            underlying: None,
             intent: false,
             status: Fail(ShouldBeInvalid),
+            violations: vec![],
         }
     );
 
@@ -259,13 +372,19 @@ fn run_test_all_invalid() {
             TestDraft {
                 word: "hello".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             },
-            &rules
+            &rules,
+            EvalMode::FirstViolation
         ),
         TestOutcome {
             word: "hello".to_string(),
+            underlying: None,
             intent: true,
             status: Fail(CustomReason(Note("Should contain ⟨a⟩".to_string()))),
+            violations: example_violations(&[(0, &[])]),
         }
     );
 
@@ -274,13 +393,19 @@ fn run_test_all_invalid() {
             TestDraft {
                 word: "ax".to_string(),
                 intent: true,
+                reason: None,
+                transcription: None,
+                underlying: None,
             },
-            &rules
+            &rules,
+            EvalMode::FirstViolation
         ),
         TestOutcome {
             word: "ax".to_string(),
+            underlying: None,
             intent: true,
             status: Fail(NoReasonGiven),
+            violations: example_violations(&[(1, &[1..2])]),
         }
     );
 }
@@ -290,16 +415,83 @@ fn validate_test_works() {
     let rules = get_example_rules();
 
     // Test should be valid
-    assert_eq!(validate_test("abc", &rules), Valid);
+    assert_eq!(
+        validate_test("abc", &rules, EvalMode::FirstViolation).unwrap(),
+        Valid
+    );
 
     // Test should be invalid - with custom reason
     assert_eq!(
-        validate_test("boc", &rules),
-        Invalid(Some(Note("Should contain ⟨a⟩".to_string())))
+        validate_test("boc", &rules, EvalMode::FirstViolation).unwrap(),
+        Invalid(example_violations(&[(0, &[])]))
     );
 
     // Test should be invalid - with no reason given
-    assert_eq!(validate_test("axe", &rules), Invalid(None));
+    assert_eq!(
+        validate_test("axe", &rules, EvalMode::FirstViolation).unwrap(),
+        Invalid(example_violations(&[(1, &[])]))
+    );
+
+    // Only first violated rule
+    assert_eq!(
+        validate_test("box", &rules, EvalMode::FirstViolation).unwrap(),
+        Invalid(example_violations(&[(0, &[])]))
+    );
+    // All violated rules
+    assert_eq!(
+        validate_test("box", &rules, EvalMode::AllViolations).unwrap(),
+        Invalid(example_violations(&[(0, &[]), (1, &[])]))
+    );
+}
+
+#[test]
+fn run_all_violations_works() {
+    let draft = Draft::from(
+        "~<>\n*Should contain ⟨a⟩\n+ a\n! x\n*Should not double letters\n! (.)\\1\n?+ boxx",
+    )
+    .unwrap();
+
+    // First violation only
+    let outcome = draft.run();
+    let Some(Message::Test(test)) = outcome.messages.last() else {
+        panic!("Last message should be a test");
+    };
+    assert_eq!(
+        test.status,
+        Fail(CustomReason(Note("Should contain ⟨a⟩".to_string())))
+    );
+    assert_eq!(
+        test.violations
+            .iter()
+            .map(|rule| rule.location.line)
+            .collect::<Vec<_>>(),
+        vec![3]
+    );
+
+    // All violations, with same reason
+    let outcome = draft.run_with(EvalMode::AllViolations);
+    let Some(Message::Test(test)) = outcome.messages.last() else {
+        panic!("Last message should be a test");
+    };
+    assert_eq!(
+        test.status,
+        Fail(CustomReason(Note("Should contain ⟨a⟩".to_string())))
+    );
+    assert_eq!(
+        test.violations
+            .iter()
+            .map(|rule| (rule.location.line, rule.intent, rule.note.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (3, true, Some(Note("Should contain ⟨a⟩".to_string()))),
+            (4, false, Some(Note("Should contain ⟨a⟩".to_string()))),
+            (
+                6,
+                false,
+                Some(Note("Should not double letters".to_string()))
+            ),
+        ]
+    );
 }
 
 /// Tests that should match (valid)
@@ -308,16 +500,16 @@ fn get_status_all_valid() {
     let my_note = Note("Some reason".to_string());
 
     // Valid test matches (pass)
-    assert_eq!(get_status(Valid, true), Pass);
+    assert_eq!(get_status(&Valid, true, None), Pass);
 
     // Valid test does not match (fail)...
     // ...with custom reason
     assert_eq!(
-        get_status(Invalid(Some(my_note)), true),
+        get_status(&invalid(Some(my_note)), true, None),
         Fail(CustomReason(Note("Some reason".to_string())))
     );
     // ...with no reason given
-    assert_eq!(get_status(Invalid(None), true), Fail(NoReasonGiven));
+    assert_eq!(get_status(&invalid(None), true, None), Fail(NoReasonGiven));
 }
 
 /// Tests that should not match (invalid)
@@ -326,11 +518,128 @@ fn get_status_all_invalid() {
     let my_note = Note("Some reason".to_string());
 
     // Invalid test does not match (pass)
-    assert_eq!(get_status(Valid, false), Fail(ShouldBeInvalid));
+    assert_eq!(get_status(&Valid, false, None), Fail(ShouldBeInvalid));
 
     // Invalid test matches (fail)...
     // ...with custom reason
-    assert_eq!(get_status(Invalid(Some(my_note)), false), Pass);
+    assert_eq!(get_status(&invalid(Some(my_note)), false, None), Pass);
     // ...or no reason given (same status)
-    assert_eq!(get_status(Invalid(None), false), Pass);
+    assert_eq!(get_status(&invalid(None), false, None), Pass);
+}
+
+/// Tests that should not match (invalid), with an expected reason
+#[test]
+fn get_status_expected_reason() {
+    let my_note = Note("Some reason".to_string());
+    let other_note = Note("Other reason".to_string());
+
+    // Invalid for expected reason (pass)
+    assert_eq!(
+        get_status(&invalid(Some(my_note.clone())), false, Some(&my_note)),
+        Pass
+    );
+
+    // Invalid for different reason (fail)
+    assert_eq!(
+        get_status(&invalid(Some(other_note.clone())), false, Some(&my_note)),
+        Fail(WrongReason(my_note.clone(), Some(other_note)))
+    );
+    // ...or no reason given
+    assert_eq!(
+        get_status(&invalid(None), false, Some(&my_note)),
+        Fail(WrongReason(my_note.clone(), None))
+    );
+
+    // Valid, so reason is not checked
+    assert_eq!(
+        get_status(&Valid, false, Some(&my_note)),
+        Fail(ShouldBeInvalid)
+    );
+}
+
+#[test]
+fn run_test_returns_rule_error() {
+    let draft =
+        Draft::from("~<>\n@backtrack-limit 100\n! (a*)*\\1b\n?+ aaaaaaaaaaaaaaaaaaaa").unwrap();
+
+    // Rule exceeds backtrack limit
+    assert!(matches!(
+        validate_test("aaaaaaaaaaaaaaaaaaaa", &draft.rules, EvalMode::FirstViolation),
+        Err(Error::RuleFail(word, Location { line: 3, .. }, _)) if word == "aaaaaaaaaaaaaaaaaaaa"
+    ));
+
+    // Test fails, instead of panicking
+    let outcome = draft.run();
+    assert_eq!(outcome.fail_count, 1);
+    assert!(matches!(
+        outcome.messages.first(),
+        Some(Message::Test(TestOutcome {
+            status: Fail(RuleError(Location { line: 3, .. }, _)),
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn violation_spans_works() {
+    let rule = |pattern: &str, intent: bool| Rule {
+        pattern: Regex::new(pattern).unwrap(),
+        source: pattern.to_string(),
+        intent,
+        note: None,
+        location: Default::default(),
+    };
+
+    // Every match of `!` rule, as character indexes
+    assert_eq!(
+        violation_spans("ŋaaxaa", &rule("aa", false)),
+        vec![1..3, 4..6]
+    );
+
+    // Region between longest matching prefix and suffix of `+` rule
+    assert_eq!(
+        violation_spans("tayxoka", &rule("^[ptk]?[aeiou]([ptk][aeiou])*$", true)),
+        [2..4]
+    );
+    // Prefix and suffix overlap
+    assert_eq!(
+        violation_spans("taaso", &rule("^[aeiou]?([ptks][aeiou])+$", true)),
+        [2..3]
+    );
+    // Cannot be determined
+    assert_eq!(violation_spans("boc", &rule("a", true)), vec![]);
+}
+
+#[test]
+fn run_expected_reason_works() {
+    let draft = Draft::from(
+        "*Invalid letters\n+ ^[ptkaeiou]+$\n*Vowel clusters\n! [aeiou]{2}\n\
+        ?!(Vowel clusters) taaka tokii\n?!(Vowel clusters) taaxa",
+    )
+    .unwrap();
+
+    let outcome = draft.run();
+    assert_eq!(outcome.fail_count, 1);
+
+    let statuses: Vec<_> = outcome
+        .messages
+        .iter()
+        .filter_map(|msg| match msg {
+            Message::Test(test) => Some(&test.status),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        statuses,
+        vec![
+            &Pass,
+            &Pass,
+            // First violated rule is not the expected reason
+            &Fail(WrongReason(
+                Note("Vowel clusters".to_string()),
+                Some(Note("Invalid letters".to_string()))
+            )),
+        ]
+    );
 }
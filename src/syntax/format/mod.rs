@@ -0,0 +1,547 @@
+#[cfg(test)]
+mod tests;
+
+use super::{split_condition, Node, Statement, StatementKind, SyntaxTree};
+use crate::draft::{replace_angle_brackets, Mode};
+
+/// Spaces for each level of indentation
+const INDENT: &str = "  ";
+
+/// Options for `SyntaxTree::format`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatOptions {
+    /// Style of class name brackets in patterns
+    ///
+    /// If `None`, the style which is used most in the file is kept
+    pub brackets: Option<Brackets>,
+    /// Maximum width of lines of tests, before words are wrapped onto another test statement
+    pub width: usize,
+}
+
+/// Style of class name brackets in patterns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Brackets {
+    /// `<` and `>`
+    Ascii,
+    /// `⟨` and `⟩`
+    Unicode,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            brackets: None,
+            width: 80,
+        }
+    }
+}
+
+/// Single line of formatted file, before indentation and alignment
+#[derive(Debug)]
+enum Line {
+    /// Blank line, between groups of statements
+    Blank,
+    /// Comment on its own line
+    Comment(String),
+    /// Statement, with lines of code, and comment after it (optional)
+    Statement {
+        kind: StatementKind,
+        code: Vec<String>,
+        multiline: bool,
+        comment: Option<String>,
+    },
+}
+
+impl SyntaxTree {
+    /// Format file in canonical style
+    ///
+    /// - Every statement is on its own line, with spaces normalized
+    /// - Rules are indented under notes, and tests are indented under rules
+    /// - Comments on their own line are indented the same as the next statement
+    /// - Patterns of consecutive classes, and consecutive comments after statements, are aligned
+    /// - Class name brackets in patterns all use the same style
+    /// - Tests are wrapped onto multiple statements, if longer than the width
+    /// - Multiple blank lines are joined
+    ///
+    /// The meaning of the file is not changed, and formatting twice gives the same result
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use phonet::syntax::{SyntaxTree, FormatOptions, Brackets};
+    /// let file = "$C=[ptk];$Vowel = [aeiou]\n*Note\n+^ <C>+$ ;# Comment\n?+ ka   ta";
+    ///
+    /// let options = FormatOptions {
+    ///     brackets: Some(Brackets::Unicode),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     SyntaxTree::from(file).format(&options),
+    ///     "$C     = [ptk]\n$Vowel = [aeiou]\n* Note\n  + ^ ⟨C⟩+$ ;# Comment\n    ?+ ka ta\n"
+    /// );
+    /// ```
+    pub fn format(&self, options: &FormatOptions) -> String {
+        let brackets = options
+            .brackets
+            .unwrap_or_else(|| self.most_used_brackets());
+
+        let lines = self.lines(brackets);
+        let indents = indents(&lines);
+
+        // Width of name of class, for each line, aligned with consecutive classes
+        // Classes with a condition are not aligned
+        let class_widths = aligned_widths(&lines, |line| match line {
+            Line::Statement {
+                kind: StatementKind::Class,
+                code,
+                multiline: false,
+                ..
+            } if !code[0].starts_with('[') => code[0]
+                .split_once('=')
+                .map(|(name, _)| name.chars().count()),
+            _ => None,
+        });
+
+        let mut output: Vec<String> = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let indent = INDENT.repeat(indents[index]);
+
+            match line {
+                Line::Blank => output.push(String::new()),
+
+                Line::Comment(comment) => output.push(format!("{}{}", indent, comment)),
+
+                Line::Statement {
+                    kind,
+                    code,
+                    multiline,
+                    comment,
+                } => {
+                    let mut code = code.clone();
+
+                    // Align class patterns
+                    if let (Some(width), Some((name, pattern))) =
+                        (class_widths[index], code[0].clone().split_once('='))
+                    {
+                        code[0] = format!("{:width$}={}", name, pattern, width = width);
+                    }
+
+                    let mut statement_lines: Vec<String> = if *multiline {
+                        // First line, inner lines indented further, and closing `;`
+                        let mut lines = vec![format!("{}{}", indent, code[0])];
+                        for inner in &code[1..] {
+                            lines.push(format!("{}{}{}", indent, INDENT, inner));
+                        }
+                        lines.push(format!("{};", indent));
+                        lines
+                    } else if *kind == StatementKind::Test {
+                        wrap_test(&code[0], &indent, options.width)
+                    } else {
+                        vec![format!("{}{}", indent, code[0])]
+                    };
+
+                    // Comment after statement
+                    // Alignment is done after all lines are built
+                    if let Some(comment) = comment {
+                        let last = statement_lines.last_mut().expect("Should have a line");
+                        if *multiline {
+                            // `;` of multiline statement comes before comment
+                            last.push_str(comment);
+                        } else {
+                            last.push_str(&format!("\0;{}", comment));
+                        }
+                    }
+
+                    output.extend(statement_lines);
+                }
+            }
+        }
+
+        let output = align_comments(output);
+
+        if output.is_empty() {
+            return String::new();
+        }
+        output.join("\n") + "\n"
+    }
+
+    /// Get style of class name brackets, which is used most in patterns of file
+    ///
+    /// Uses `Brackets::Ascii` if neither is used more
+    fn most_used_brackets(&self) -> Brackets {
+        let (mut ascii, mut unicode) = (0, 0);
+
+        for statement in self.statements() {
+            if !has_pattern(statement.kind) {
+                continue;
+            }
+            unicode += statement.text.matches('⟨').count();
+            ascii += replace_angle_brackets(&statement.text).matches('⟨').count()
+                - statement.text.matches('⟨').count();
+        }
+
+        if unicode > ascii {
+            Brackets::Unicode
+        } else {
+            Brackets::Ascii
+        }
+    }
+
+    /// Convert nodes to lines, with statements normalized
+    fn lines(&self, brackets: Brackets) -> Vec<Line> {
+        let mut lines = Vec::new();
+
+        // Amount of line breaks since last statement or comment
+        let mut newlines = 0;
+
+        for node in &self.nodes {
+            match node {
+                Node::Newline => newlines += 1,
+                // Line breaks in whitespace are only in multiline statements, before the `;`
+                Node::Whitespace(_) | Node::Separator => (),
+
+                Node::Comment(comment) => {
+                    // Comment on the same line as the last statement
+                    if newlines == 0 {
+                        if let Some(Line::Statement { comment: last @ None, .. }) =
+                            lines.last_mut()
+                        {
+                            *last = Some(comment.trim_end().to_string());
+                            continue;
+                        }
+                    }
+
+                    push_blank(&mut lines, newlines);
+                    lines.push(Line::Comment(comment.trim_end().to_string()));
+                    newlines = 0;
+                }
+
+                Node::Statement(statement) => {
+                    push_blank(&mut lines, newlines);
+                    lines.push(format_statement(statement, brackets));
+                    newlines = 0;
+                }
+            }
+        }
+
+        lines
+    }
+}
+
+/// Push blank line, if more than one line break was between the last line, and the line is not the first
+fn push_blank(lines: &mut Vec<Line>, newlines: usize) {
+    if newlines > 1 && !lines.is_empty() {
+        lines.push(Line::Blank);
+    }
+}
+
+/// Returns `true` if the statement kind has a regex pattern, which can contain class names
+fn has_pattern(kind: StatementKind) -> bool {
+    matches!(kind, StatementKind::Class | StatementKind::Rule)
+}
+
+/// Normalize statement, with class name brackets in style
+fn format_statement(statement: &Statement, brackets: Brackets) -> Line {
+    let Statement {
+        kind,
+        text,
+        multiline,
+        ..
+    } = statement;
+
+    let code = if *multiline {
+        // Keep line breaks, but normalize spaces of each line
+        text.lines()
+            .map(|line| collapse_spaces(line.trim()))
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                if has_pattern(*kind) {
+                    convert_brackets(&line, brackets)
+                } else {
+                    line
+                }
+            })
+            .collect()
+    } else {
+        vec![format_single_line(*kind, text, brackets)]
+    };
+
+    Line::Statement {
+        kind: *kind,
+        code,
+        multiline: *multiline,
+        comment: None,
+    }
+}
+
+/// Normalize statement which is on a single line
+///
+/// Statements which are invalid are only trimmed
+fn format_single_line(kind: StatementKind, text: &str, brackets: Brackets) -> String {
+    // `[tags] statement`
+    if let Some((condition, rest)) = split_condition(text) {
+        let statement = format_single_line(kind, rest, brackets);
+        return format!(
+            "[{}] {}",
+            condition.split_whitespace().collect::<Vec<_>>().join(" "),
+            statement
+        );
+    }
+
+    let mut chars = text.chars();
+    let operator = chars.next().unwrap_or_default();
+    let rest = chars.as_str().trim();
+
+    match kind {
+        // `~<name>`
+        StatementKind::Mode => {
+            let mut chars = rest.chars();
+            let (first, last) = (chars.next(), chars.next_back());
+            match (Mode::from_options(first, last), first, last) {
+                (Some(_), Some(first), Some(last)) => {
+                    format!("~{}{}{}", first, chars.as_str().trim(), last)
+                }
+                _ => text.to_string(),
+            }
+        }
+
+        // `$name = pattern`
+        StatementKind::Class => match rest.split_once('=') {
+            Some((name, pattern)) => {
+                let pattern = convert_brackets(&collapse_spaces(pattern.trim()), brackets);
+                format!("${} = {}", name.trim(), pattern)
+                    .trim_end()
+                    .to_string()
+            }
+            None => text.to_string(),
+        },
+
+        // `+ pattern` or `! pattern`
+        StatementKind::Rule => {
+            let pattern = convert_brackets(&collapse_spaces(rest), brackets);
+            format!("{} {}", operator, pattern).trim_end().to_string()
+        }
+
+        // `* note` or `*: note`
+        StatementKind::Note => match rest.strip_prefix(':') {
+            Some(note) => format!("*: {}", note.trim()).trim_end().to_string(),
+            None => format!("* {}", rest).trim_end().to_string(),
+        },
+
+        // `?+ words`, `?! words`, or `?!(reason) words`
+        StatementKind::Test => {
+            let mut chars = rest.chars();
+            let intent = chars.next();
+            let after = chars.as_str();
+
+            let (prefix, words) = match (intent, after.strip_prefix('(')) {
+                (Some('!'), Some(reason)) => match reason.split_once(')') {
+                    Some((reason, words)) => (format!("?!({})", reason.trim()), words),
+                    None => return text.to_string(),
+                },
+                (Some(intent @ ('+' | '!')), _) => (format!("?{}", intent), after),
+                _ => return text.to_string(),
+            };
+
+            let words: Vec<_> = words.split_whitespace().collect();
+            if words.is_empty() {
+                prefix
+            } else {
+                format!("{} {}", prefix, words.join(" "))
+            }
+        }
+
+        // `@name argument`
+        StatementKind::Directive => format!("@{}", collapse_spaces(rest)),
+
+        StatementKind::Unknown => text.to_string(),
+    }
+}
+
+/// Replace consecutive spaces with a single space
+fn collapse_spaces(text: &str) -> String {
+    text.split(' ')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Convert class name brackets of pattern to style
+///
+/// `<` and `>` of look-behinds and named groups are not converted
+fn convert_brackets(pattern: &str, brackets: Brackets) -> String {
+    match brackets {
+        Brackets::Unicode => replace_angle_brackets(pattern),
+        Brackets::Ascii => pattern.replace('⟨', "<").replace('⟩', ">"),
+    }
+}
+
+/// Get indentation level of each line
+///
+/// Rules are indented under notes, and tests are indented under rules
+///
+/// Comments and blank lines use the indentation of the next statement
+fn indents(lines: &[Line]) -> Vec<usize> {
+    let mut indents = Vec::new();
+
+    // Whether any note or rule has been defined yet
+    let mut has_note = false;
+    let mut has_rule = false;
+
+    for line in lines {
+        let Line::Statement { kind, .. } = line else {
+            indents.push(None);
+            continue;
+        };
+
+        let rule_indent = has_note as usize;
+        indents.push(Some(match kind {
+            StatementKind::Rule => {
+                has_rule = true;
+                rule_indent
+            }
+            StatementKind::Test => rule_indent + has_rule as usize,
+            StatementKind::Note => {
+                has_note = true;
+                0
+            }
+            _ => 0,
+        }));
+    }
+
+    // Use indentation of next statement, for comments and blank lines
+    let mut next = 0;
+    let mut indents: Vec<usize> = indents
+        .into_iter()
+        .rev()
+        .map(|indent| {
+            if let Some(indent) = indent {
+                next = indent;
+            }
+            next
+        })
+        .collect();
+    indents.reverse();
+    indents
+}
+
+/// Get width to align each line to, for consecutive lines which have a width
+///
+/// Returns `None` for lines without a width
+fn aligned_widths(lines: &[Line], width: impl Fn(&Line) -> Option<usize>) -> Vec<Option<usize>> {
+    let widths: Vec<_> = lines.iter().map(width).collect();
+    let mut aligned = widths.clone();
+
+    // Maximum width of each group of consecutive lines
+    let mut start = 0;
+    while start < widths.len() {
+        let end = widths[start..]
+            .iter()
+            .position(Option::is_none)
+            .map_or(widths.len(), |index| start + index);
+
+        let max = widths[start..end].iter().flatten().max().copied();
+        for width in &mut aligned[start..end] {
+            *width = max;
+        }
+
+        start = end + 1;
+    }
+
+    aligned
+}
+
+/// Align comments after statements, for consecutive lines with comments
+///
+/// The position of the comment is marked with a `\0` character
+fn align_comments(lines: Vec<String>) -> Vec<String> {
+    let widths = lines
+        .iter()
+        .map(|line| line.find('\0').map(|index| line[..index].chars().count()))
+        .collect::<Vec<_>>();
+
+    let mut aligned = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = widths[start..]
+            .iter()
+            .position(Option::is_none)
+            .map_or(lines.len(), |index| start + index);
+
+        let max = widths[start..end]
+            .iter()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap_or(0);
+        for (line, width) in lines[start..end].iter().zip(&widths[start..end]) {
+            let padding = " ".repeat(max - width.unwrap_or(max) + 1);
+            aligned.push(line.replacen('\0', &padding, 1));
+        }
+
+        if end < lines.len() {
+            aligned.push(lines[end].clone());
+        }
+        start = end + 1;
+    }
+
+    aligned
+}
+
+/// Split test into multiple statements, so each line is not longer than the width (if possible)
+///
+/// Each statement has the same intent and reason
+fn wrap_test(test: &str, indent: &str, width: usize) -> Vec<String> {
+    // Condition is repeated for every statement
+    let (indent, test) = match split_condition(test) {
+        Some((condition, rest)) => (format!("{}[{}] ", indent, condition), rest),
+        None => (indent.to_string(), test),
+    };
+    let indent = indent.as_str();
+
+    // Invalid tests are not wrapped
+    if !test.starts_with("?+") && !test.starts_with("?!") {
+        return vec![format!("{}{}", indent, test)];
+    }
+
+    // Intent, and reason (optional), which may contain spaces
+    let end = match test.find(')') {
+        Some(index) if test.starts_with("?!(") => index + 1,
+        _ => test.find(' ').unwrap_or(test.len()),
+    };
+    let (prefix, words) = test.split_at(end);
+
+    // Words, with transcriptions kept with their word, such as `kato /kato/`
+    let is_slashed = |word: &str| word.len() > 2 && word.starts_with('/') && word.ends_with('/');
+    let mut units: Vec<String> = Vec::new();
+    for word in words.split(' ').filter(|word| !word.is_empty()) {
+        match units.last_mut() {
+            Some(last) if is_slashed(word) && !last.contains(' ') && !is_slashed(last) => {
+                last.push(' ');
+                last.push_str(word);
+            }
+            _ => units.push(word.to_string()),
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut line = format!("{}{}", indent, prefix);
+    let mut is_empty = true;
+
+    for word in &units {
+        // Start new statement, if word does not fit
+        if !is_empty && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(line);
+            line = format!("{}{}", indent, prefix);
+        }
+
+        line.push(' ');
+        line.push_str(word);
+        is_empty = false;
+    }
+
+    lines.push(line);
+    lines
+}
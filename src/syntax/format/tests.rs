@@ -0,0 +1,222 @@
+use super::*;
+use crate::Draft;
+
+/// Format file with default options
+fn format(file: &str) -> String {
+    SyntaxTree::from(file).format(&Default::default())
+}
+
+/// Format file with bracket style
+fn format_brackets(file: &str, brackets: Brackets) -> String {
+    SyntaxTree::from(file).format(&FormatOptions {
+        brackets: Some(brackets),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn format_examples_works() {
+    let files = [
+        include_str!("../../../examples/example.phonet"),
+        include_str!("../../../examples/tokipona.phonet"),
+        include_str!("../../../examples/esperanto.phonet"),
+    ];
+
+    for file in files {
+        let formatted = format(file);
+
+        // Formatting twice gives the same result
+        assert_eq!(format(&formatted), formatted);
+
+        // Meaning of file is not changed
+        let draft = Draft::from(file).unwrap();
+        let formatted = Draft::from(&formatted).unwrap();
+        assert_eq!(formatted.rules, draft.rules);
+        assert_eq!(formatted.messages, draft.messages);
+        assert_eq!(formatted.mode, draft.mode);
+        assert_eq!(formatted.name, draft.name);
+    }
+}
+
+#[test]
+fn format_indents_works() {
+    assert_eq!(
+        format("?+ a\n+ a\n  ?+ a\n    * Note\n?! b\n+ b\n?+ b\n# Comment\n?! c"),
+        [
+            "?+ a",
+            "+ a",
+            "  ?+ a",
+            "* Note",
+            "    ?! b",
+            "  + b",
+            "    ?+ b",
+            "    # Comment",
+            "    ?! c",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn format_statements_works() {
+    assert_eq!(
+        format("~ < My   lang >; $C=[ptk] ;  +^  <C>+$;!a;@backtrack-limit   10\n*:  Quiet ;*   Note"),
+        [
+            "~<My   lang>",
+            "$C = [ptk]",
+            "+ ^ <C>+$",
+            "! a",
+            "@backtrack-limit 10",
+            "*: Quiet",
+            "* Note",
+            "",
+        ]
+        .join("\n")
+    );
+
+    // Invalid statements are only trimmed
+    assert_eq!(
+        format("  %  abc  \n ~{} \n$ C \n?x a  b\n?!(unclosed a b"),
+        "%  abc\n~{}\n$ C\n?x a  b\n?!(unclosed a b\n"
+    );
+}
+
+#[test]
+fn format_blank_lines_works() {
+    assert_eq!(format(""), "");
+    assert_eq!(format("\n\n  \n"), "");
+    assert_eq!(format("\n\n+ a\n\n\n\n+ b\n+ c\n\n"), "+ a\n\n+ b\n+ c\n");
+}
+
+#[test]
+fn format_class_alignment_works() {
+    assert_eq!(
+        format("$C = [ptk]\n$Vowel = [aeiou]\n$_ = <C>|<Vowel>\n\n$Nasal=[mn]\n$N2 = x"),
+        [
+            "$C     = [ptk]",
+            "$Vowel = [aeiou]",
+            "$_     = <C>|<Vowel>",
+            "",
+            "$Nasal = [mn]",
+            "$N2    = x",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn format_comments_works() {
+    assert_eq!(
+        format("$C = [ptk] ;# Consonants\n$Vowel = [aeiou];   # Vowels  \n  # Rules\n+ a;#A\n\n+ aaa ;# AAA\n+ b; # B"),
+        [
+            "$C     = [ptk]   ;# Consonants",
+            "$Vowel = [aeiou] ;# Vowels",
+            "# Rules",
+            "+ a ;#A",
+            "",
+            "+ aaa ;# AAA",
+            "+ b   ;# B",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn format_brackets_works() {
+    let file = "$C = ⟨V⟩\n$V = <C>\n+ (?<x>.) \\k<x> (?<=<C>) (?P<y>⟨V⟩)";
+
+    assert_eq!(
+        format_brackets(file, Brackets::Ascii),
+        "$C = <V>\n$V = <C>\n+ (?<x>.) \\k<x> (?<=<C>) (?P<y><V>)\n"
+    );
+    assert_eq!(
+        format_brackets(file, Brackets::Unicode),
+        "$C = ⟨V⟩\n$V = ⟨C⟩\n+ (?<x>.) \\k<x> (?<=⟨C⟩) (?P<y>⟨V⟩)\n"
+    );
+
+    // Most used style
+    assert_eq!(format("+ ⟨V⟩ ⟨C⟩ <_>"), "+ ⟨V⟩ ⟨C⟩ ⟨_⟩\n");
+    assert_eq!(format("+ ⟨V⟩ <C> (?<x>.)"), "+ <V> <C> (?<x>.)\n");
+
+    // Only patterns are converted
+    assert_eq!(format("* <a> ⟨b⟩\n?+ <a>"), "* <a> ⟨b⟩\n  ?+ <a>\n");
+}
+
+#[test]
+fn format_test_wrapping_works() {
+    let options = FormatOptions {
+        width: 16,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        SyntaxTree::from("* Note\n?+ aaa bbb   ccc dddddddddddddd e\n?!(Some reason) a b").format(&options),
+        [
+            "* Note",
+            "  ?+ aaa bbb ccc",
+            "  ?+ dddddddddddddd",
+            "  ?+ e",
+            "  ?!(Some reason) a",
+            "  ?!(Some reason) b",
+            "",
+        ]
+        .join("\n")
+    );
+
+    // Tests without words are kept
+    assert_eq!(SyntaxTree::from("?+").format(&options), "?+\n");
+
+    // Transcriptions are kept with their word
+    assert_eq!(
+        SyntaxTree::from("?+ aaaa bbb /bbb/ /c/ d").format(&options),
+        "?+ aaaa\n?+ bbb /bbb/ /c/\n?+ d\n"
+    );
+}
+
+#[test]
+fn format_multiline_works() {
+    assert_eq!(
+        format("* Note\n+ a\n?! &   \n     taaso    ttaso\n\n ttaso   ; # Comment\n$C = a &\n b;"),
+        [
+            "* Note",
+            "  + a",
+            "    ?! &",
+            "      taaso ttaso",
+            "      ttaso",
+            "    ;# Comment",
+            "$C = a &",
+            "  b",
+            ";",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn format_conditions_works() {
+    let options = FormatOptions {
+        width: 20,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        SyntaxTree::from("$C=a\n[ strict   !loose]$V =  b\n[strict]*Note\n[strict]+ a  b\n[strict] ?+ aaa bbb ccc\n[strict +a")
+            .format(&options),
+        [
+            "$C = a",
+            "[strict !loose] $V = b",
+            "[strict] * Note",
+            "  [strict] + a b",
+            "    [strict] ?+ aaa",
+            "    [strict] ?+ bbb",
+            "    [strict] ?+ ccc",
+            "[strict +a",
+            "",
+        ]
+        .join("\n")
+    );
+}
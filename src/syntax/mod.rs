@@ -0,0 +1,280 @@
+#[cfg(test)]
+mod tests;
+
+/// Format file from syntax tree
+mod format;
+
+use std::fmt;
+
+use crate::draft::is_class_intersection;
+
+pub use self::format::{Brackets, FormatOptions};
+
+/// Lossless concrete syntax tree of a *Phonet* file
+///
+/// Unlike `Draft`, this keeps comments, whitespace, `;` separators and multiline statements,
+/// so the original file can be recreated exactly with `to_string`
+///
+/// Statements are split the same as when parsing a `Draft`, but are not parsed or validated
+///
+/// # Examples
+///
+/// ```
+/// # use phonet::syntax::{SyntaxTree, Node, StatementKind};
+/// let file = "$C = [ptk] ;# Consonants\n* Note\n  + ^ <C>+ $\n";
+///
+/// let tree = SyntaxTree::from(file);
+///
+/// assert_eq!(tree.to_string(), file);
+/// assert_eq!(tree.statements().count(), 3);
+/// assert!(matches!(&tree.nodes[3], Node::Comment(comment) if comment == "# Consonants"));
+/// assert_eq!(tree.statements().last().unwrap().kind, StatementKind::Rule);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxTree {
+    /// Every node of the file, in order
+    pub nodes: Vec<Node>,
+}
+
+/// Single node of `SyntaxTree`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    /// Spaces and tabs, before or after a statement or comment
+    ///
+    /// May contain line breaks, if at the end of a multiline statement
+    Whitespace(String),
+    /// Line break
+    Newline,
+    /// `;`, which ends a statement
+    Separator,
+    /// Comment, beginning with `#`, until the end of the line or a `;`
+    Comment(String),
+    /// Statement, with original text
+    Statement(Statement),
+}
+
+/// Statement of `SyntaxTree`, with original text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    /// Kind of statement, from its operator
+    pub kind: StatementKind,
+    /// Original text of statement, without whitespace before or after
+    ///
+    /// Multiline statements include `&` and line breaks
+    pub text: String,
+    /// Line number of beginning of statement
+    pub line: usize,
+    /// Whether statement is multiline, started with `&` and ended with `;`
+    pub multiline: bool,
+}
+
+/// Kind of `Statement`, from its operator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatementKind {
+    /// `~`
+    Mode,
+    /// `$`
+    Class,
+    /// `+` or `!`
+    Rule,
+    /// `?`
+    Test,
+    /// `*`
+    Note,
+    /// `@`
+    Directive,
+    /// Any other operator, which is invalid
+    Unknown,
+}
+
+impl SyntaxTree {
+    /// Parse syntax tree from file
+    ///
+    /// This never fails, as statements are not validated
+    pub fn from(file: &str) -> Self {
+        let mut nodes = Vec::new();
+
+        // Original text of current statement or comment
+        let mut text = String::new();
+        // Text of statement, as built in `split_statements`, to check for comments
+        let mut build_line = String::new();
+        // Line number of beginning of multiline, if multiline is active
+        let mut multiline: Option<usize> = None;
+        // Current statement is a comment
+        let mut is_comment = false;
+        // Current line number
+        let mut line = 1;
+
+        for (byte, ch) in file.char_indices() {
+            match ch {
+                // End of statement, without multiline
+                '\n' | ';' if multiline.is_none() => {
+                    push_text(&mut nodes, &text, line, false);
+                    nodes.push(if ch == '\n' {
+                        Node::Newline
+                    } else {
+                        Node::Separator
+                    });
+
+                    text.clear();
+                    build_line.clear();
+                    is_comment = false;
+                }
+
+                // End of multiline statement
+                ';' => {
+                    push_text(&mut nodes, &text, multiline.unwrap_or(line), true);
+                    nodes.push(Node::Separator);
+
+                    text.clear();
+                    build_line.clear();
+                    is_comment = false;
+                    multiline = None;
+                }
+
+                // Line break in multiline statement
+                '\n' => {
+                    text.push(ch);
+                    build_line.push(' ');
+                }
+
+                // Start multiline, if not already active, and not an intersection of classes
+                '&' if !is_comment && !is_class_intersection(&build_line, &file[byte + 1..]) => {
+                    text.push(ch);
+                    if multiline.is_none() {
+                        multiline = Some(line);
+                        build_line.push(' ');
+                    } else {
+                        build_line.push(ch);
+                    }
+                }
+
+                // Comment at start of statement
+                '#' if build_line.trim().is_empty() => {
+                    text.push(ch);
+                    is_comment = true;
+                }
+
+                // Other character
+                _ => {
+                    text.push(ch);
+                    if !is_comment {
+                        build_line.push(ch);
+                    }
+                }
+            }
+
+            if ch == '\n' {
+                line += 1;
+            }
+        }
+
+        // Last statement, which may not be ended
+        push_text(&mut nodes, &text, multiline.unwrap_or(line), multiline.is_some());
+
+        Self { nodes }
+    }
+
+    /// Get every statement in tree, in order
+    pub fn statements(&self) -> impl Iterator<Item = &Statement> {
+        self.nodes.iter().filter_map(|node| match node {
+            Node::Statement(statement) => Some(statement),
+            _ => None,
+        })
+    }
+
+    /// Get every comment in tree, in order, including the `#`
+    pub fn comments(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().filter_map(|node| match node {
+            Node::Comment(comment) => Some(comment.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    /// Recreate the original file
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for node in &self.nodes {
+            match node {
+                Node::Whitespace(text) | Node::Comment(text) => write!(f, "{}", text)?,
+                Node::Newline => writeln!(f)?,
+                Node::Separator => write!(f, ";")?,
+                Node::Statement(Statement { text, .. }) => write!(f, "{}", text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StatementKind {
+    /// Get kind of statement from its text
+    ///
+    /// Whitespace and `&` before the operator, and a condition (such as `[strict]`), are ignored
+    pub fn from_text(text: &str) -> Self {
+        use StatementKind::*;
+
+        let text = text.trim_start_matches(|ch: char| ch.is_whitespace() || ch == '&');
+        let text = split_condition(text).map_or(text, |(_, rest)| rest);
+
+        match text
+            .chars()
+            .find(|ch| !ch.is_whitespace() && *ch != '&')
+        {
+            Some('~') => Mode,
+            Some('$') => Class,
+            Some('+' | '!') => Rule,
+            Some('?') => Test,
+            Some('*') => Note,
+            Some('@') => Directive,
+            _ => Unknown,
+        }
+    }
+}
+
+/// Split condition of statement (such as `[strict]`) from the rest of the statement, if it has one
+///
+/// Returns `None` if the statement has no condition, or the condition is not closed
+pub(crate) fn split_condition(text: &str) -> Option<(&str, &str)> {
+    let (condition, rest) = text.strip_prefix('[')?.split_once(']')?;
+    Some((condition, rest.trim_start()))
+}
+
+/// Push text of statement or comment as nodes, with whitespace before and after
+fn push_text(nodes: &mut Vec<Node>, text: &str, line: usize, multiline: bool) {
+    if text.is_empty() {
+        return;
+    }
+
+    let trimmed = text.trim_start();
+    let before = &text[..text.len() - trimmed.len()];
+    let trimmed = trimmed.trim_end();
+    let after = &text[before.len() + trimmed.len()..];
+
+    if !before.is_empty() {
+        nodes.push(Node::Whitespace(before.to_string()));
+    }
+
+    if !trimmed.is_empty() {
+        // Comment, if `#` is the first character, other than `&`
+        let is_comment = trimmed
+            .chars()
+            .find(|ch| !ch.is_whitespace() && *ch != '&')
+            == Some('#');
+
+        nodes.push(if is_comment {
+            Node::Comment(trimmed.to_string())
+        } else {
+            Node::Statement(Statement {
+                kind: StatementKind::from_text(trimmed),
+                text: trimmed.to_string(),
+                line,
+                multiline,
+            })
+        });
+    }
+
+    if !after.is_empty() {
+        nodes.push(Node::Whitespace(after.to_string()));
+    }
+}
@@ -0,0 +1,107 @@
+use super::*;
+
+#[test]
+fn syntax_tree_is_lossless() {
+    let files = [
+        include_str!("../../examples/example.phonet"),
+        include_str!("../../examples/tokipona.phonet"),
+        "",
+        "\n\n;;\n",
+        "foo bar & abc 123;\nbaz &\n123 456\n abc\n;\na &\n\nwhat\n; hello;1; 2;3 & a;4\npls",
+        "# a comment\nhello & a & b\n;\n# comment no-multiline & ect\nsome statement # with a hashtag",
+        "  $C = [ptk]  \t;#comment;  \r\n* note ⟨ŋ⟩ \n?! &\n  a\n  b\n  ;# after",
+        "$A = <B> & <C>\n+ <A> &\n<B>;",
+    ];
+
+    for file in files {
+        assert_eq!(SyntaxTree::from(file).to_string(), file);
+    }
+}
+
+#[test]
+fn syntax_tree_nodes_works() {
+    let tree = SyntaxTree::from("  $C = [ptk] ;# C\n\n?! &\n  a\n  ;*x");
+
+    let statement = |kind, text: &str, line, multiline| {
+        Node::Statement(Statement {
+            kind,
+            text: text.to_string(),
+            line,
+            multiline,
+        })
+    };
+
+    assert_eq!(
+        tree.nodes,
+        vec![
+            Node::Whitespace("  ".to_string()),
+            statement(StatementKind::Class, "$C = [ptk]", 1, false),
+            Node::Whitespace(" ".to_string()),
+            Node::Separator,
+            Node::Comment("# C".to_string()),
+            Node::Newline,
+            Node::Newline,
+            statement(StatementKind::Test, "?! &\n  a", 3, true),
+            Node::Whitespace("\n  ".to_string()),
+            Node::Separator,
+            statement(StatementKind::Note, "*x", 5, false),
+        ]
+    );
+
+    assert_eq!(tree.comments().collect::<Vec<_>>(), vec!["# C"]);
+}
+
+#[test]
+fn syntax_tree_class_intersection_works() {
+    let tree = SyntaxTree::from("$A = <B> & <C>\n+ <A> &\n<B>;");
+    let statements: Vec<_> = tree
+        .statements()
+        .map(|statement| (statement.text.as_str(), statement.multiline))
+        .collect();
+
+    assert_eq!(
+        statements,
+        vec![("$A = <B> & <C>", false), ("+ <A> &\n<B>", true)]
+    );
+}
+
+#[test]
+fn syntax_tree_matches_draft() {
+    use crate::Draft;
+
+    let file = include_str!("../../examples/example.phonet");
+    let draft = Draft::from(file).unwrap();
+
+    // Lines of rules
+    let lines: Vec<_> = SyntaxTree::from(file)
+        .statements()
+        .filter(|statement| statement.kind == StatementKind::Rule)
+        .map(|statement| statement.line)
+        .collect();
+
+    assert_eq!(
+        lines,
+        draft
+            .rules
+            .iter()
+            .map(|rule| rule.location.line)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn statement_kind_from_text_works() {
+    use StatementKind::*;
+
+    assert_eq!(StatementKind::from_text("~<>"), Mode);
+    assert_eq!(StatementKind::from_text("$C = a"), Class);
+    assert_eq!(StatementKind::from_text("+ a"), Rule);
+    assert_eq!(StatementKind::from_text("! a"), Rule);
+    assert_eq!(StatementKind::from_text("& ?+ a"), Test);
+    assert_eq!(StatementKind::from_text("*: a"), Note);
+    assert_eq!(StatementKind::from_text("@include a"), Directive);
+    assert_eq!(StatementKind::from_text("[strict !loose] + a"), Rule);
+    assert_eq!(StatementKind::from_text("[strict + a"), Unknown);
+    assert_eq!(StatementKind::from_text("% a"), Unknown);
+    assert_eq!(StatementKind::from_text(""), Unknown);
+}
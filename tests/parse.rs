@@ -29,8 +29,10 @@ fn example_draft_works() {
         rules.next().unwrap(),
         &Rule {
             pattern: Regex::new(r"^(?:(?:[ptkmnswjl])|(?:[aeiou]))+$").unwrap(),
+            source: "^ ⟨_⟩+ $".to_string(),
             intent: true,
             note: Some(Note("Invalid letters".to_string())),
+            location: Default::default(),
         },
     );
 
@@ -38,8 +40,10 @@ fn example_draft_works() {
         rules.next().unwrap(),
         &Rule {
             pattern: Regex::new(r"^(?:[aeiou])?((?:[ptkmnswjl])(?:[aeiou]))+$").unwrap(),
+            source: "^ ⟨V⟩? ( ⟨C⟩ ⟨V⟩ )+ $".to_string(),
             intent: true,
             note: Some(Note("Syllable structure".to_string())),
+            location: Default::default(),
         }
     );
 
@@ -47,8 +51,10 @@ fn example_draft_works() {
         rules.next().unwrap(),
         &Rule {
             pattern: Regex::new(r"(.)\1").unwrap(),
+            source: r"(.)\1".to_string(),
             intent: false,
             note: Some(Note("No repeated letters".to_string())),
+            location: Default::default(),
         }
     );
 
@@ -56,8 +62,10 @@ fn example_draft_works() {
         rules.next().unwrap(),
         &Rule {
             pattern: Regex::new(r"(?<x>.)\k<x>").unwrap(),
+            source: r"(?<x> .) \k<x>".to_string(),
             intent: false,
             note: Some(Note("No repeated letters".to_string())),
+            location: Default::default(),
         }
     );
 
@@ -71,17 +79,11 @@ fn example_draft_works() {
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "taso".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "taso"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "tyxo".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "tyxo"))
     );
 
     assert_eq!(
@@ -90,17 +92,11 @@ fn example_draft_works() {
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "tyxo".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "tyxo"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "taso".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "taso"))
     );
 
     assert_eq!(
@@ -109,45 +105,27 @@ fn example_draft_works() {
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "taso".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "taso"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "kili".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "kili"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "ano".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "ano"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "atoso".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "atoso"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "taaso".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "taaso"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "an".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "an"))
     );
 
     assert_eq!(
@@ -156,54 +134,33 @@ fn example_draft_works() {
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "silo".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "silo"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "tila".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "tila"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "akka".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "akka"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "axe".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "axe"))
     );
 
     // (quiet note)
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "taso".to_string(),
-            intent: true
-        })
+        &Message::Test(TestDraft::new(true, "taso"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "taaso".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "taaso"))
     );
     assert_eq!(
         messages.next().unwrap(),
-        &Message::Test(TestDraft {
-            word: "ttaso".to_string(),
-            intent: false
-        })
+        &Message::Test(TestDraft::new(false, "ttaso"))
     );
 
     assert_eq!(
@@ -1,14 +1,37 @@
+// Spans of words are lists of ranges, which often have only one element
+#![allow(clippy::single_range_in_vec_init)]
+
+use std::ops::Range;
+
 use phonet::{
     draft::{Message, Note},
-    outcome::{FailKind::*, PassStatus::*, TestOutcome},
+    outcome::{FailKind::*, PassStatus::*, TestOutcome, Violation},
     Draft,
 };
 
+/// Get violations of rules at each index, with spans of word
+fn violations(draft: &Draft, rules: &[(usize, &[Range<usize>])]) -> Vec<Violation> {
+    rules
+        .iter()
+        .map(|(index, spans)| {
+            let rule = &draft.rules[*index];
+            Violation {
+                rule: *index,
+                note: rule.note.clone(),
+                intent: rule.intent,
+                location: rule.location.clone(),
+                spans: spans.to_vec(),
+            }
+        })
+        .collect()
+}
+
 #[test]
 fn example_outcome_works() {
     let file = include_str!("../examples/example.phonet");
 
-    let outcome = Draft::from(file).expect("Failed to parse").run();
+    let draft = Draft::from(file).expect("Failed to parse");
+    let outcome = draft.run();
 
     assert_eq!(outcome.fail_count, 2);
 
@@ -22,16 +45,20 @@ fn example_outcome_works() {
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "taso".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "tyxo".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: violations(&draft, &[(0, &[1..3])]),
         })
     );
 
@@ -43,16 +70,20 @@ fn example_outcome_works() {
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "tyxo".to_string(),
+            underlying: None,
             intent: true,
             status: Fail(CustomReason(Note("Invalid letters".to_string()))),
+            violations: violations(&draft, &[(0, &[1..3])]),
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "taso".to_string(),
+            underlying: None,
             intent: false,
             status: Fail(ShouldBeInvalid),
+            violations: vec![],
         })
     );
 
@@ -64,48 +95,60 @@ fn example_outcome_works() {
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "taso".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "kili".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "ano".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "atoso".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "taaso".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: violations(&draft, &[(1, &[2..3])]),
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "an".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: violations(&draft, &[(1, &[])]),
         })
     );
 
@@ -117,32 +160,40 @@ fn example_outcome_works() {
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "silo".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "tila".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "akka".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: violations(&draft, &[(1, &[0..2])]),
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "axe".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: violations(&draft, &[(0, &[1..2])]),
         })
     );
 
@@ -151,24 +202,30 @@ fn example_outcome_works() {
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "taso".to_string(),
+            underlying: None,
             intent: true,
             status: Pass,
+            violations: vec![],
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "taaso".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: violations(&draft, &[(1, &[2..3])]),
         })
     );
     assert_eq!(
         list.next().unwrap(),
         &Message::Test(TestOutcome {
             word: "ttaso".to_string(),
+            underlying: None,
             intent: false,
             status: Pass,
+            violations: violations(&draft, &[(1, &[0..1])]),
         })
     );
 